@@ -1,5 +1,9 @@
 #![deny(clippy::print_stdout)]
 
+extern crate libc;
+#[cfg(windows)]
+extern crate winapi;
+
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
@@ -9,20 +13,17 @@ mod threading;
 fn filzl_daemons(_py: Python, m: &PyModule) -> PyResult<()> {
     #[pyfn(m)]
     #[pyo3(name = "get_thread_cpu_time")]
-    fn get_thread_cpu_time(py: Python, thread_id: usize) -> PyResult<PyObject> {
-        if cfg!(debug_assertions) {
-            println!("Running in debug mode");
-        }
-
-        let result = unsafe { threading::platform::get_thread_cpu_usage(thread_id) };
+    fn get_thread_cpu_time(_py: Python, thread_id: usize) -> PyResult<f64> {
+        #[cfg(unix)]
+        let result = unsafe { threading::platform::get_thread_cpu_usage(thread_id as libc::pthread_t) };
+        #[cfg(windows)]
+        let result = unsafe {
+            threading::platform::get_thread_cpu_usage(thread_id as winapi::um::winnt::HANDLE)
+        };
 
-        match result {
-            Ok(result) => {
-                let result_py: PyObject = result.to_object(py);
-                Ok(result_py.into())
-            }
-            Err(_err) => Err(PyValueError::new_err("Unable to get thread CPU time")),
-        }
+        result
+            .map(|duration| duration.as_secs_f64())
+            .map_err(|err| PyValueError::new_err(err.to_string()))
     }
 
     Ok(())
@@ -1,8 +1,35 @@
 extern crate lazy_static;
 extern crate libc;
 
+use std::time::Duration;
+
+/// Errors surfaced while sampling a thread's CPU time. Kept distinct from a bare `String` so
+/// callers (and `lib.rs`'s pyfunction) can tell a failed syscall apart from a procfs parse
+/// failure without string-matching.
+#[derive(Debug)]
+pub enum ThreadCpuError {
+    SyscallFailed(String),
+    ParseError(String),
+}
+
+impl std::fmt::Display for ThreadCpuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ThreadCpuError::SyscallFailed(msg) => write!(f, "thread CPU syscall failed: {msg}"),
+            ThreadCpuError::ParseError(msg) => {
+                write!(f, "failed to parse thread CPU accounting data: {msg}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThreadCpuError {}
+
 #[cfg(target_os = "macos")]
 pub mod platform {
+    use super::ThreadCpuError;
+    use std::time::Duration;
+
     #[repr(C)]
     struct ThreadBasicInfo {
         user_time: libc::time_value_t,
@@ -25,7 +52,11 @@ pub mod platform {
         ) -> libc::kern_return_t;
     }
 
-    pub unsafe fn get_thread_cpu_usage(thread_id: libc::pthread_t) -> Result<f64, String> {
+    /// Returns the total (user + system) CPU time accumulated by `thread_id` via Mach's
+    /// `THREAD_BASIC_INFO` flavor. Never prints - callers decide how to surface the value.
+    pub unsafe fn get_thread_cpu_usage(
+        thread_id: libc::pthread_t,
+    ) -> Result<Duration, ThreadCpuError> {
         let thread_port = pthread_mach_thread_np(thread_id);
         let mut info = std::mem::zeroed::<ThreadBasicInfo>();
         let mut count = std::mem::size_of::<ThreadBasicInfo>() as libc::mach_msg_type_number_t
@@ -38,47 +69,159 @@ pub mod platform {
             &mut count,
         );
 
-        if result == libc::KERN_SUCCESS {
-            let user_time =
-                info.user_time.seconds as f64 + info.user_time.microseconds as f64 / 1_000_000f64;
-            let system_time = info.system_time.seconds as f64
-                + info.system_time.microseconds as f64 / 1_000_000f64;
-            println!(
-                "CPU Time: User = {} s, System = {} s",
-                user_time, system_time
-            );
-            Ok(user_time + system_time)
-        } else {
-            println!("Failed to get thread CPU usage.");
-            Err("Failed to get thread CPU usage.".to_string())
+        if result != libc::KERN_SUCCESS {
+            return Err(ThreadCpuError::SyscallFailed(format!(
+                "thread_info returned {result}"
+            )));
         }
+
+        let user = Duration::new(
+            info.user_time.seconds as u64,
+            (info.user_time.microseconds as u32) * 1_000,
+        );
+        let system = Duration::new(
+            info.system_time.seconds as u64,
+            (info.system_time.microseconds as u32) * 1_000,
+        );
+
+        Ok(user + system)
     }
 }
 
 #[cfg(target_os = "linux")]
 pub mod platform {
-    pub unsafe fn get_thread_cpu_usage(thread_id: libc::pthread_t) {
+    use super::ThreadCpuError;
+    use std::time::Duration;
+
+    /// Returns the total CPU time accumulated by `thread_id`. Prefers the
+    /// `pthread_getcpuclockid`/`clock_gettime(CLOCK_THREAD_CPUTIME_ID)` pair, falling back to
+    /// parsing `/proc/self/task/<tid>/stat` when that clock can't be resolved (e.g. under some
+    /// sandboxes). The procfs path ignores `thread_id` and reads the calling thread's own stats,
+    /// since `pthread_t` has no portable mapping back to the kernel TID visible under `/proc`;
+    /// the clock-based path does measure the thread `thread_id` names.
+    pub unsafe fn get_thread_cpu_usage(
+        thread_id: libc::pthread_t,
+    ) -> Result<Duration, ThreadCpuError> {
+        match get_thread_cpu_usage_clock(thread_id) {
+            Ok(duration) => Ok(duration),
+            Err(_) => get_thread_cpu_usage_procfs(),
+        }
+    }
+
+    unsafe fn get_thread_cpu_usage_clock(
+        thread_id: libc::pthread_t,
+    ) -> Result<Duration, ThreadCpuError> {
         let mut clock_id: libc::clockid_t = 0;
+        if libc::pthread_getcpuclockid(thread_id, &mut clock_id) != 0 {
+            return Err(ThreadCpuError::SyscallFailed(
+                "pthread_getcpuclockid failed".to_string(),
+            ));
+        }
+
         let mut ts = libc::timespec {
             tv_sec: 0,
             tv_nsec: 0,
         };
+        if libc::clock_gettime(clock_id, &mut ts) != 0 {
+            return Err(ThreadCpuError::SyscallFailed(
+                "clock_gettime failed".to_string(),
+            ));
+        }
 
-        libc::pthread_getcpuclockid(thread_id, &mut clock_id);
-        libc::clock_gettime(clock_id, &mut ts);
+        Ok(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+    }
+
+    /// Reads `utime` (field 14) and `stime` (field 15) out of `/proc/self/task/<tid>/stat`,
+    /// skipping straight past the `comm` field by scanning from the line's final `')'` - the
+    /// thread name between the parens can itself contain spaces and parentheses, which would
+    /// otherwise throw off a naive whitespace split.
+    fn get_thread_cpu_usage_procfs() -> Result<Duration, ThreadCpuError> {
+        let tid = unsafe { libc::syscall(libc::SYS_gettid) };
+        let path = format!("/proc/self/task/{tid}/stat");
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| ThreadCpuError::SyscallFailed(format!("failed to read {path}: {e}")))?;
+
+        let after_comm = contents
+            .rfind(')')
+            .map(|idx| &contents[idx + 1..])
+            .ok_or_else(|| {
+                ThreadCpuError::ParseError(format!("missing ')' in stat line: {contents}"))
+            })?;
+
+        // `after_comm` starts at field 3 (`state`); fields 14/15 overall are therefore indices
+        // 11/12 in this zero-indexed, already-comm-stripped split.
+        let fields: Vec<&str> = after_comm.split_whitespace().collect();
+        let utime: u64 = fields
+            .get(11)
+            .and_then(|f| f.parse().ok())
+            .ok_or_else(|| ThreadCpuError::ParseError("missing utime field".to_string()))?;
+        let stime: u64 = fields
+            .get(12)
+            .and_then(|f| f.parse().ok())
+            .ok_or_else(|| ThreadCpuError::ParseError("missing stime field".to_string()))?;
+
+        let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks_per_sec <= 0 {
+            return Err(ThreadCpuError::SyscallFailed(
+                "sysconf(_SC_CLK_TCK) failed".to_string(),
+            ));
+        }
+
+        let total_ticks = utime + stime;
+        let secs = total_ticks / ticks_per_sec as u64;
+        let remainder_ticks = total_ticks % ticks_per_sec as u64;
+        let nanos = (remainder_ticks as f64 / ticks_per_sec as f64 * 1_000_000_000f64) as u32;
+
+        Ok(Duration::new(secs, nanos))
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub mod platform {
+    extern crate winapi;
+
+    use super::ThreadCpuError;
+    use std::time::Duration;
+    use winapi::shared::minwindef::FILETIME;
+    use winapi::um::processthreadsapi::GetThreadTimes;
+    use winapi::um::winnt::HANDLE;
+
+    /// Returns the total (kernel + user) CPU time accumulated by `thread_handle`, converting
+    /// the 100ns-resolution `FILETIME` pair `GetThreadTimes` reports into a [`Duration`].
+    pub unsafe fn get_thread_cpu_usage(thread_handle: HANDLE) -> Result<Duration, ThreadCpuError> {
+        let mut creation_time = std::mem::zeroed::<FILETIME>();
+        let mut exit_time = std::mem::zeroed::<FILETIME>();
+        let mut kernel_time = std::mem::zeroed::<FILETIME>();
+        let mut user_time = std::mem::zeroed::<FILETIME>();
 
-        println!(
-            "CPU Time: {} seconds, {} nanoseconds",
-            ts.tv_sec, ts.tv_nsec
+        let result = GetThreadTimes(
+            thread_handle,
+            &mut creation_time,
+            &mut exit_time,
+            &mut kernel_time,
+            &mut user_time,
         );
-        Ok(ts.tv_sec + (ts.tv_nsec / 1_000_000_000f64))
+
+        if result == 0 {
+            return Err(ThreadCpuError::SyscallFailed(
+                "GetThreadTimes failed".to_string(),
+            ));
+        }
+
+        let total_100ns = filetime_to_u64(&kernel_time) + filetime_to_u64(&user_time);
+        Ok(Duration::from_nanos(total_100ns * 100))
+    }
+
+    fn filetime_to_u64(time: &FILETIME) -> u64 {
+        ((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64
     }
 }
+
 #[cfg(test)]
 mod tests {
-    extern crate libc;
     use super::*;
     use std::sync::mpsc;
+    use std::thread;
 
     extern "C" {
         fn pthread_self() -> libc::pthread_t;
@@ -87,7 +230,7 @@ mod tests {
     fn full_cpu_utilization() {
         // Heavy computation task
         let mut prime_numbers = Vec::new();
-        for num in 2..100000 {
+        for num in 2..100_000 {
             if (2..num).all(|divisor| num % divisor != 0) {
                 prime_numbers.push(num);
             }
@@ -95,32 +238,25 @@ mod tests {
     }
 
     #[test]
+    #[cfg(unix)]
     fn test_thread_cpu_usage() {
-        // Sleep for 3 seconds (no CPU usage)
-        // Then perform heavy computation
+        // Sleep for 3 seconds (no CPU usage), then perform heavy computation, sampling CPU time
+        // before and after to make sure it tracks actual work rather than wall-clock idle time.
         let (tx, rx) = mpsc::channel();
 
         let child = thread::spawn(move || {
-            unsafe {
-                let thread_id = libc::pthread_self();
-                tx.send(thread_id).expect("Failed to send thread id");
-            }
+            let thread_id = unsafe { pthread_self() };
+            tx.send(thread_id).expect("Failed to send thread id");
 
-            // Simulate idle time
             thread::sleep(Duration::from_secs(3));
-
-            // Actually run full CPU computation
             full_cpu_utilization();
         });
 
         let thread_id = rx.recv().expect("Did not receive thread id");
-        println!("Thread ID: {:?}", thread_id);
 
-        // Wait for 3 seconds
         thread::sleep(Duration::from_secs(3));
 
-        // Measure the thread; it should really be near zero but because of race conditions / CPU load
-        // we just enforce it to be less than 0.1
+        // Measured from idle time; should be near zero but we allow slack for scheduler noise.
         let cpu_usage = unsafe { platform::get_thread_cpu_usage(thread_id) };
         assert!(
             cpu_usage.is_ok(),
@@ -128,16 +264,14 @@ mod tests {
         );
         if let Ok(usage) = cpu_usage {
             assert!(
-                usage < 0.1,
-                "Expected CPU usage to be less than 0.1 after idle time"
+                usage < Duration::from_millis(100),
+                "Expected CPU usage to be less than 100ms after idle time, got {usage:?}"
             );
         }
 
-        // Now we wait a final 3 seconds. At this point the thread should be only working
-        // on its heavy computation
         thread::sleep(Duration::from_secs(3));
 
-        // Measure the thread; it should be near 3 seconds
+        // Measured after the heavy computation; should be close to the full 3 seconds of work.
         let cpu_usage = unsafe { platform::get_thread_cpu_usage(thread_id) };
         assert!(
             cpu_usage.is_ok(),
@@ -145,12 +279,11 @@ mod tests {
         );
         if let Ok(usage) = cpu_usage {
             assert!(
-                usage > 2.5,
-                "Expected CPU usage to be greater than 2.5 of full computation"
+                usage > Duration::from_millis(2_500),
+                "Expected CPU usage to be greater than 2.5s of full computation, got {usage:?}"
             );
         }
 
-        // Wait for the child thread to finish its computation
         child.join().expect("Child thread panicked");
     }
 }
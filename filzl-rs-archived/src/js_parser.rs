@@ -1,58 +1,91 @@
+use std::collections::HashSet;
 use tree_sitter::TreeCursor;
 
-pub fn find_use_server_instances(cursor: &mut TreeCursor, code: &str, instances: &mut Vec<String>) {
-    /*
-     * The useServer() init functions are the main entrypoints for the serverside injection of
-     * variables into the client runtime. These are synthetic functions that will be filled in
-     * by the server when results are dynamically generated.
-     */
+/// A `useServer()`/`Server.useServer()` call site along with the property paths read off of it.
+/// Returned instead of raw strings so callers don't have to re-derive which properties belong to
+/// which server-injected instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerInstance {
+    /// How the call result was bound: a bare identifier (`const x = useServer()`) or a set of
+    /// destructured names (`const { foo, bar } = useServer()`).
+    pub binding: ServerBinding,
+    /// Member-expression paths accessed off of the bound identifier, e.g. `x.foo`, `x?.bar`,
+    /// `x["baz"]`. Empty for destructured bindings, since the destructuring itself already
+    /// names the accessed properties.
+    pub accessed_properties: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerBinding {
+    Identifier(String),
+    Destructured(Vec<String>),
+}
+
+/// Optional sink for tracing what the scanner is visiting, replacing the old unconditional
+/// `println!` calls so production callers aren't forced to eat the stdout noise.
+pub type DebugCallback<'a> = &'a dyn Fn(&str);
+
+/// Node kinds that introduce a new lexical scope for the purposes of shadowing detection. This
+/// isn't exhaustive for every TS construct (e.g. `for` loop head bindings), but it covers the
+/// shapes that matter for catching an inner re-declaration of a `useServer()` binding.
+fn introduces_scope(kind: &str) -> bool {
+    matches!(
+        kind,
+        "statement_block"
+            | "function_declaration"
+            | "function_expression"
+            | "arrow_function"
+            | "method_definition"
+    )
+}
+
+/// Walks the tree looking for `useServer()` call sites, returning each instance's binding.
+/// Handles:
+/// - bare identifier bindings: `const x = useServer()`
+/// - destructured bindings: `const { foo, bar } = useServer()`
+/// - aliased/namespaced calls: `const x = Server.useServer()`
+/// - generic type arguments: `const x = useServer<Props>()`
+pub fn find_use_server_instances(
+    cursor: &mut TreeCursor,
+    code: &str,
+    instances: &mut Vec<ServerBinding>,
+    debug: Option<DebugCallback>,
+) {
     loop {
         let node = cursor.node();
-        println!(
-            "Visiting node: {} [{}]",
-            node.kind(),
-            code[node.start_byte()..node.end_byte()].trim()
-        );
-
-        if node.kind() == "function_body" || node.kind() == "arrow_function" {
-            cursor.goto_first_child();
+        if let Some(debug) = debug {
+            debug(&format!(
+                "Visiting node: {} [{}]",
+                node.kind(),
+                code[node.start_byte()..node.end_byte()].trim()
+            ));
         }
 
         if node.kind() == "variable_declarator" {
-            // Get first child
-            // Check whether it's an identifier
-            // Then, check whether the call_expression is the second child
             let mut child_cursor = node.walk();
 
             if child_cursor.goto_first_child() {
-                let identifier = child_cursor.node();
-                if identifier.kind() == "identifier" {
-                    let identifier_name =
-                        code[identifier.start_byte()..identifier.end_byte()].trim();
-                    println!("Found identifier: {}", identifier_name);
+                let binding_node = child_cursor.node();
 
+                if child_cursor.goto_next_sibling() && child_cursor.node().kind() == "=" {
                     if child_cursor.goto_next_sibling() {
-                        if child_cursor.node().kind() == "=" {
-                            if child_cursor.goto_next_sibling() {
-                                // Expected variable assignment
-                                let call_expression = child_cursor.node();
-                                println!("NEXT SIBLING: {}", call_expression.kind());
-                                if call_expression.kind() == "call_expression" {
-                                    // Here, check if the call_expression meets your criteria
-                                    // For example, if it's a call to `useServer()`
-                                    // Then, you can add `identifier_name` to `instances`
-                                    let function_name = get_call_function(&mut child_cursor, code);
-                                    println!(
-                                        "Found call expression: {} {} {:?}",
-                                        &code[call_expression.start_byte()
-                                            ..call_expression.end_byte()],
-                                        identifier_name,
-                                        function_name
-                                    );
-                                    if function_name == Some("useServer".to_string()) {
-                                        instances.push(identifier_name.to_string());
-                                    }
+                        let value_node = child_cursor.node();
+                        if value_node.kind() == "call_expression"
+                            && is_use_server_call(&mut value_node.walk(), code)
+                        {
+                            match binding_node.kind() {
+                                "identifier" => {
+                                    let name =
+                                        code[binding_node.start_byte()..binding_node.end_byte()]
+                                            .trim()
+                                            .to_string();
+                                    instances.push(ServerBinding::Identifier(name));
+                                }
+                                "object_pattern" => {
+                                    let names = collect_object_pattern_names(binding_node, code);
+                                    instances.push(ServerBinding::Destructured(names));
                                 }
+                                _ => {}
                             }
                         }
                     }
@@ -70,34 +103,227 @@ pub fn find_use_server_instances(cursor: &mut TreeCursor, code: &str, instances:
     }
 }
 
+/// Checks whether a `call_expression` node's callee is (possibly generically-instantiated)
+/// `useServer` - either a bare identifier or the final segment of a member expression
+/// (`Server.useServer()`).
+fn is_use_server_call(cursor: &mut TreeCursor, code: &str) -> bool {
+    get_call_function(cursor, code).as_deref() == Some("useServer")
+}
+
+fn collect_object_pattern_names(pattern: tree_sitter::Node, code: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut cursor = pattern.walk();
+
+    if cursor.goto_first_child() {
+        loop {
+            let node = cursor.node();
+            match node.kind() {
+                "shorthand_property_identifier_pattern" | "identifier" => {
+                    names.push(code[node.start_byte()..node.end_byte()].trim().to_string());
+                }
+                "pair_pattern" => {
+                    // `{ foo: renamed }` - we still care about the accessed server property
+                    // (`foo`), not the locally renamed binding.
+                    if let Some(key) = node.child_by_field_name("key") {
+                        names.push(code[key.start_byte()..key.end_byte()].trim().to_string());
+                    }
+                }
+                _ => {}
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    names
+}
+
+/// Collects the parameter names bound by a function-like node (`function_declaration`,
+/// `function_expression`, `arrow_function`, `method_definition`), including destructured and
+/// rest parameters. Used to detect a parameter re-declaring an outer `useServer()` binding, e.g.
+/// `function render(instance) { ... }` shadowing an outer `const instance = useServer()`.
+fn collect_parameter_names(node: tree_sitter::Node, code: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Some(parameters) = node.child_by_field_name("parameters") {
+        collect_pattern_names(parameters, code, &mut names);
+    }
+    names
+}
+
+/// Recursively extracts identifier names out of a parameter or binding pattern node.
+fn collect_pattern_names(node: tree_sitter::Node, code: &str, names: &mut Vec<String>) {
+    match node.kind() {
+        "identifier" => {
+            names.push(code[node.start_byte()..node.end_byte()].trim().to_string());
+        }
+        "object_pattern" => {
+            names.extend(collect_object_pattern_names(node, code));
+        }
+        "formal_parameters" | "array_pattern" => {
+            let mut cursor = node.walk();
+            if cursor.goto_first_child() {
+                loop {
+                    collect_pattern_names(cursor.node(), code, names);
+                    if !cursor.goto_next_sibling() {
+                        break;
+                    }
+                }
+            }
+        }
+        "required_parameter" | "optional_parameter" | "rest_parameter" | "assignment_pattern" => {
+            if let Some(pattern) = node.child_by_field_name("pattern") {
+                collect_pattern_names(pattern, code, names);
+            } else if let Some(left) = node.child_by_field_name("left") {
+                collect_pattern_names(left, code, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Walks the tree collecting member-expression paths rooted at `instance`, honoring lexical
+/// scope so an inner re-declaration of the same identifier name doesn't attribute its accesses
+/// back to the outer `useServer()` binding. Supports optional chaining (`x?.foo`) and computed
+/// member access with a string-literal key (`x["foo"]`).
 pub fn collect_properties(
     cursor: &mut TreeCursor,
     code: &str,
-    instance: &String,
+    instance: &str,
     results: &mut Vec<String>,
+    debug: Option<DebugCallback>,
 ) {
+    // Stack of (depth at which the scope was entered, names shadowed in that scope).
+    let mut scope_stack: Vec<(usize, HashSet<String>)> = vec![(0, HashSet::new())];
+    let mut depth: usize = 0;
+
     loop {
         let node = cursor.node();
+        if let Some(debug) = debug {
+            debug(&format!(
+                "Visiting node: {} [{}]",
+                node.kind(),
+                code[node.start_byte()..node.end_byte()].trim()
+            ));
+        }
 
-        if node.kind() == "function_body" || node.kind() == "arrow_function" {
-            cursor.goto_first_child();
+        if introduces_scope(node.kind()) {
+            let mut shadowed = HashSet::new();
+            // A function's own parameters are in scope for its body, so a parameter named the
+            // same as `instance` shadows the outer binding just like an inner `const`/`let` re-
+            // declaration would.
+            if node.kind() != "statement_block" {
+                if collect_parameter_names(node, code)
+                    .iter()
+                    .any(|name| name == instance)
+                {
+                    shadowed.insert(instance.to_string());
+                }
+            }
+            scope_stack.push((depth, shadowed));
         }
 
-        if node.kind() == "member_expression" {
-            let expression_text = code[node.start_byte()..node.end_byte()].trim().to_string();
-            println!("Found member expression: {} {}", expression_text, instance);
-            if expression_text.starts_with(instance) {
-                results.push(expression_text);
+        if node.kind() == "variable_declarator" {
+            if let Some(name_node) = node.child(0) {
+                if name_node.kind() == "identifier" {
+                    let name = code[name_node.start_byte()..name_node.end_byte()]
+                        .trim()
+                        .to_string();
+                    // The `useServer()` declarator itself is the tracked binding, not a shadow.
+                    let is_tracked_binding = node
+                        .child_by_field_name("value")
+                        .is_some_and(|value| {
+                            value.kind() == "call_expression"
+                                && is_use_server_call(&mut value.walk(), code)
+                        });
+                    if name == instance && !is_tracked_binding {
+                        if let Some((_, shadowed)) = scope_stack.last_mut() {
+                            shadowed.insert(name);
+                        }
+                    }
+                }
             }
         }
 
-        if !cursor.goto_first_child() {
-            while !cursor.goto_next_sibling() {
+        if matches!(node.kind(), "member_expression" | "subscript_expression") {
+            if !is_shadowed(instance, &scope_stack) {
+                if let Some(path) = member_access_path(node, code, instance) {
+                    results.push(path);
+                }
+            }
+        }
+
+        let descended = cursor.goto_first_child();
+        if descended {
+            depth += 1;
+        } else {
+            loop {
+                // Leaving the node at `depth`: drop any scope it introduced before moving on.
+                pop_scopes_above(&mut scope_stack, depth.saturating_sub(1));
+                if cursor.goto_next_sibling() {
+                    break;
+                }
                 if !cursor.goto_parent() {
                     return;
                 }
+                depth -= 1;
+            }
+        }
+    }
+}
+
+fn pop_scopes_above(scope_stack: &mut Vec<(usize, HashSet<String>)>, depth: usize) {
+    while scope_stack.len() > 1 && scope_stack.last().map(|(d, _)| *d).unwrap_or(0) > depth {
+        scope_stack.pop();
+    }
+}
+
+fn is_shadowed(instance: &str, scope_stack: &[(usize, HashSet<String>)]) -> bool {
+    scope_stack
+        .iter()
+        .any(|(_, shadowed)| shadowed.contains(instance))
+}
+
+/// Renders the textual access path for a member/subscript expression rooted at `instance`,
+/// e.g. `x.foo`, `x?.foo`, `x["foo"]`. Returns `None` for computed access with a non-literal key
+/// (e.g. `x[someVar]`), since the accessed property name can't be determined statically.
+fn member_access_path(node: tree_sitter::Node, code: &str, instance: &str) -> Option<String> {
+    match node.kind() {
+        "member_expression" => {
+            let object = node.child_by_field_name("object")?;
+            let property = node.child_by_field_name("property")?;
+            let object_text = code[object.start_byte()..object.end_byte()].trim();
+
+            if object_text != instance {
+                return None;
             }
+
+            let optional = node
+                .child(1)
+                .map(|n| code[n.start_byte()..n.end_byte()].trim() == "?.")
+                .unwrap_or(false);
+            let separator = if optional { "?." } else { "." };
+            let property_text = code[property.start_byte()..property.end_byte()].trim();
+            Some(format!("{}{}{}", object_text, separator, property_text))
         }
+        "subscript_expression" => {
+            let object = node.child_by_field_name("object")?;
+            let index = node.child_by_field_name("index")?;
+            let object_text = code[object.start_byte()..object.end_byte()].trim();
+
+            if object_text != instance {
+                return None;
+            }
+
+            if index.kind() == "string" {
+                let index_text = code[index.start_byte()..index.end_byte()].trim();
+                Some(format!("{}[{}]", object_text, index_text))
+            } else {
+                None
+            }
+        }
+        _ => None,
     }
 }
 
@@ -116,6 +342,8 @@ fn get_call_function(cursor: &mut TreeCursor, code: &str) -> Option<String> {
                         .to_string(),
                 ),
                 "member_expression" => get_member_expression_name(&mut child_cursor, code),
+                // `useServer<Props>()` - the callee is still the first child even when a
+                // generic type-argument list follows it before the parenthesized arguments.
                 _ => None,
             }
         } else {
@@ -129,18 +357,107 @@ fn get_call_function(cursor: &mut TreeCursor, code: &str) -> Option<String> {
 fn get_member_expression_name(cursor: &mut TreeCursor, code: &str) -> Option<String> {
     let node = cursor.node();
 
-    if node.kind() == "member_expression" && cursor.goto_first_child() {
-        match cursor.node().kind() {
-            "identifier" => Some(
-                cursor
-                    .node()
-                    .utf8_text(code.as_bytes())
-                    .unwrap()
-                    .to_string(),
-            ),
-            _ => None,
-        }
-    } else {
-        None
+    if node.kind() == "member_expression" {
+        // We want the final segment of the member expression (`Server.useServer` -> `useServer`)
+        // so that namespaced calls are recognized the same way bare identifier calls are.
+        let property = node.child_by_field_name("property")?;
+        return Some(
+            property
+                .utf8_text(code.as_bytes())
+                .unwrap_or_default()
+                .to_string(),
+        );
+    }
+
+    let _ = cursor;
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn parse(code: &str) -> tree_sitter::Tree {
+        let mut parser = Parser::new();
+        parser
+            .set_language(tree_sitter_typescript::language_tsx())
+            .expect("Error loading TSX grammar");
+        parser.parse(code, None).expect("Failed to parse code")
+    }
+
+    fn collect(code: &str, instance: &str) -> Vec<String> {
+        let tree = parse(code);
+        let mut results = Vec::new();
+        let mut cursor = tree.root_node().walk();
+        collect_properties(&mut cursor, code, instance, &mut results, None);
+        results
+    }
+
+    #[test]
+    fn collects_accesses_outside_any_inner_scope() {
+        let code = r#"
+            const instance = useServer();
+            const value = instance.foo;
+        "#;
+        assert_eq!(collect(code, "instance"), vec!["instance.foo"]);
+    }
+
+    #[test]
+    fn variable_redeclaration_in_a_nested_block_shadows_the_outer_binding() {
+        let code = r#"
+            const instance = useServer();
+            function render() {
+                const instance = { other: 1 };
+                return instance.other;
+            }
+        "#;
+        assert_eq!(
+            collect(code, "instance"),
+            Vec::<String>::new(),
+            "the inner `const instance` should shadow the outer useServer() binding"
+        );
+    }
+
+    #[test]
+    fn function_parameter_redeclaration_shadows_the_outer_binding() {
+        let code = r#"
+            const instance = useServer();
+            function render(instance) {
+                return instance.other;
+            }
+        "#;
+        assert_eq!(
+            collect(code, "instance"),
+            Vec::<String>::new(),
+            "a parameter named `instance` should shadow the outer useServer() binding"
+        );
+    }
+
+    #[test]
+    fn arrow_function_parameter_redeclaration_shadows_the_outer_binding() {
+        let code = r#"
+            const instance = useServer();
+            const render = (instance) => instance.other;
+        "#;
+        assert_eq!(
+            collect(code, "instance"),
+            Vec::<String>::new(),
+            "an arrow function parameter named `instance` should shadow the outer binding"
+        );
+    }
+
+    #[test]
+    fn sibling_scopes_do_not_leak_shadowing_into_each_other() {
+        let code = r#"
+            const instance = useServer();
+            function shadowsHere(instance) {
+                return instance.other;
+            }
+            function doesNotShadow() {
+                return instance.foo;
+            }
+        "#;
+        assert_eq!(collect(code, "instance"), vec!["instance.foo"]);
     }
 }
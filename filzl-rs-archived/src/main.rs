@@ -6,6 +6,8 @@ use tree_sitter::Parser;
 
 mod js_parser;
 
+use js_parser::{ServerBinding, ServerInstance};
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     if args.len() != 2 {
@@ -25,15 +27,38 @@ fn main() {
     let tree = parser.parse(&code, None).expect("Failed to parse code");
     let root_node = tree.root_node();
 
+    let trace = std::env::var("FILZL_TRACE").is_ok();
+    let debug = |message: &str| {
+        if trace {
+            eprintln!("{}", message);
+        }
+    };
+    let debug_callback: Option<&dyn Fn(&str)> = if trace { Some(&debug) } else { None };
+
     let mut cursor = root_node.walk();
-    let mut use_server_instances = Vec::new();
-    js_parser::find_use_server_instances(&mut cursor, &code, &mut use_server_instances);
+    let mut bindings = Vec::new();
+    js_parser::find_use_server_instances(&mut cursor, &code, &mut bindings, debug_callback);
 
-    let mut results = Vec::new();
-    for instance in use_server_instances {
-        let mut cursor = root_node.walk();
-        js_parser::collect_properties(&mut cursor, &code, &instance, &mut results);
+    let mut server_instances = Vec::new();
+    for binding in bindings {
+        match binding {
+            ServerBinding::Identifier(ref name) => {
+                let mut results = Vec::new();
+                let mut cursor = root_node.walk();
+                js_parser::collect_properties(&mut cursor, &code, name, &mut results, debug_callback);
+                server_instances.push(ServerInstance {
+                    binding: ServerBinding::Identifier(name.clone()),
+                    accessed_properties: results,
+                });
+            }
+            ServerBinding::Destructured(names) => {
+                server_instances.push(ServerInstance {
+                    binding: ServerBinding::Destructured(names),
+                    accessed_properties: Vec::new(),
+                });
+            }
+        }
     }
 
-    println!("Extracted values: {:?}", results);
+    println!("Extracted server instances: {:#?}", server_instances);
 }
@@ -0,0 +1,55 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::fs::File;
+use std::io::Read;
+use std::sync::Arc;
+
+use mountaineer::{build_snapshot, Ssr};
+
+fn build_composite_file(paths: &[&str]) -> String {
+    // Assume we're being called from the project root, where the Cargo.toml is located
+    let base_path = "src/benches/fixtures/";
+    let mut content = String::new();
+    for path in paths {
+        let full_path = format!("{}{}", base_path, path);
+        let mut file = File::open(full_path).expect("Error opening file");
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .expect("Unable to read to string");
+
+        content += "\n\n";
+        content += &contents;
+    }
+    content
+}
+
+fn cold_render(static_prefix: String, entry_point: String) -> String {
+    // Recompiles the polyfill + framework static prefix alongside the page-specific entry point
+    // on every call - this is what every render pays for today.
+    let js = Ssr::new(format!("{}\n\n{}", static_prefix, entry_point), "SSR");
+    js.render_to_string(None).unwrap()
+}
+
+fn snapshot_render(snapshot: Arc<[u8]>, entry_point: String) -> String {
+    // The static prefix is already compiled and executed inside `snapshot`, so this only
+    // compiles and runs the small page-specific entry point.
+    let js = Ssr::with_snapshot(entry_point, "SSR", snapshot);
+    js.render_to_string(None).unwrap()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let static_prefix = build_composite_file(&["ssr_polyfill_archive.js", "react_runtime.js"]);
+    let entry_point = build_composite_file(&["home_controller_ssr_with_react.js"]);
+
+    let snapshot: Arc<[u8]> = Arc::from(build_snapshot(&static_prefix));
+
+    c.bench_function("cold_render", |b| {
+        b.iter(|| cold_render(black_box(static_prefix.clone()), black_box(entry_point.clone())))
+    });
+
+    c.bench_function("snapshot_render", |b| {
+        b.iter(|| snapshot_render(black_box(snapshot.clone()), black_box(entry_point.clone())))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,13 +1,26 @@
+//! An SWC-based bundler predating [`crate::bundle_common`]'s Rolldown pipeline. Neither
+//! `compile_production_bundle` nor any of the other `lib.rs` entrypoints call [`bundle`] - those
+//! all go through Rolldown now - so this is an opt-in, not-yet-adopted alternative rather than
+//! part of the crate's default Python-facing API. See [`crate::dependencies`] for the same
+//! `tsconfig.json`/`node_modules` resolution semantics implemented against SWC's own bundler
+//! traits instead of as a standalone dependency graph.
+
+use anyhow::Context;
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use swc::{config::{IsModule, Options}, try_with_handler};
-use swc_bundler::{Bundler, ModuleData, ModuleRecord};
+use swc::{config::Options, try_with_handler};
+use swc_bundler::{Bundler, Load, ModuleData, ModuleRecord, Resolve};
 use swc_common::{
     errors::{ColorConfig, Handler},
-    FileName, FilePathMapping, SourceMap, GLOBALS,
-    EsVersion,
+    EsVersion, FileName, FilePathMapping, Globals, SourceMap, GLOBALS,
 };
-use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax, TsConfig};
+use swc_ecma_codegen::{text_writer::JsWriter, Emitter};
+use swc_ecma_parser::{lexer::Lexer, EsSyntax, Parser, StringInput, Syntax, TsSyntax};
+
+use crate::dependencies::{resolve_candidate, resolve_exports_map, resolve_on_disk, split_package_specifier};
 
 pub struct BundleOptions {
     pub entry_points: Vec<String>,
@@ -17,6 +30,9 @@ pub struct BundleOptions {
     pub is_server: bool,
     pub live_reload_port: i32,
     pub outdir: Option<String>,
+    /// Optional `tsconfig.json` to read compiler settings (`target`, JSX pragma) and module
+    /// resolution hints (`baseUrl`, `paths`) from. See [`load_tsconfig`].
+    pub tsconfig_path: Option<String>,
 }
 
 pub struct BundleResult {
@@ -25,85 +41,402 @@ pub struct BundleResult {
     pub path: PathBuf,
 }
 
+/// `tsconfig.json` `compilerOptions` keys SWC's [`Options`] has no slot to consume, dropped during
+/// [`load_tsconfig`] the same way SWC's own tooling (e.g. `@swc/cli`'s tsconfig bridging) drops
+/// `tsc`-only settings rather than erroring on them - these are real, meaningful `tsc` options,
+/// just ones a bundling step like this one can't act on.
+const IGNORED_COMPILER_OPTIONS: &[&str] = &[
+    "moduleResolution",
+    "lib",
+    "types",
+    "typeRoots",
+    "skipLibCheck",
+    "forceConsistentCasingInFileNames",
+    "incremental",
+    "composite",
+    "declaration",
+    "declarationMap",
+    "emitDeclarationOnly",
+    "noEmit",
+    "isolatedModules",
+    "allowJs",
+    "checkJs",
+    "resolveJsonModule",
+];
+
+/// The `compilerOptions` [`NodeModulesResolver`] consults directly, since unlike `target` or the
+/// JSX pragma, SWC's [`Options`] has no field for `baseUrl`/`paths` to merge into.
+#[derive(Debug, Default, Clone)]
+struct TsconfigSettings {
+    base_url: Option<PathBuf>,
+    paths: Vec<(String, Vec<String>)>,
+}
+
+/// Reads and parses `tsconfig_path`'s `compilerOptions`, filtering out
+/// [`IGNORED_COMPILER_OPTIONS`] first, then merges the remainder onto `swc_options` the way
+/// `Object.assign(defaults, userConfig)` would - a recognized option overrides whatever `Options`
+/// already set, an unrecognized one (`strict`, `esModuleInterop`, ...) is silently dropped since
+/// it's a real `tsc` option this bundler has no use for. `baseUrl`/`paths` aren't represented in
+/// `Options` at all, so they're returned separately for [`NodeModulesResolver`] to consult.
+fn load_tsconfig(tsconfig_path: &Path, swc_options: &mut Options) -> anyhow::Result<TsconfigSettings> {
+    let contents = fs::read_to_string(tsconfig_path)
+        .with_context(|| format!("Failed to read tsconfig at {:?}", tsconfig_path))?;
+    let config: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse tsconfig at {:?}", tsconfig_path))?;
+
+    let mut settings = TsconfigSettings::default();
+
+    let Some(compiler_options) = config.get("compilerOptions").and_then(Value::as_object) else {
+        return Ok(settings);
+    };
+
+    let tsconfig_dir = tsconfig_path.parent().unwrap_or_else(|| Path::new("."));
+
+    for (key, value) in compiler_options {
+        if IGNORED_COMPILER_OPTIONS.contains(&key.as_str()) {
+            continue;
+        }
+
+        match key.as_str() {
+            "target" => {
+                if let Some(target) = value.as_str().and_then(parse_es_version) {
+                    swc_options.config.jsc.target = Some(target);
+                }
+            }
+            "jsxFactory" => {
+                if let Some(pragma) = value.as_str() {
+                    let react = &mut swc_options
+                        .config
+                        .jsc
+                        .transform
+                        .get_or_insert_with(Default::default)
+                        .react;
+                    react.pragma = Some(pragma.to_string());
+                }
+            }
+            "jsxFragmentFactory" => {
+                if let Some(pragma_frag) = value.as_str() {
+                    let react = &mut swc_options
+                        .config
+                        .jsc
+                        .transform
+                        .get_or_insert_with(Default::default)
+                        .react;
+                    react.pragma_frag = Some(pragma_frag.to_string());
+                }
+            }
+            "baseUrl" => {
+                if let Some(base_url) = value.as_str() {
+                    settings.base_url = Some(tsconfig_dir.join(base_url));
+                }
+            }
+            "paths" => {
+                if let Some(paths) = value.as_object() {
+                    for (alias, targets) in paths {
+                        let targets: Vec<String> = targets
+                            .as_array()
+                            .map(|arr| {
+                                arr.iter()
+                                    .filter_map(|t| t.as_str().map(str::to_string))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        settings.paths.push((alias.clone(), targets));
+                    }
+                }
+            }
+            // Everything else (`strict`, `esModuleInterop`, `experimentalDecorators`, ...) is a
+            // real `tsc` option this bundler has no slot for - neither forwarded into `Options`
+            // nor consulted by the resolver - so it's dropped same as an explicitly ignored one.
+            _ => {}
+        }
+    }
+
+    if !settings.paths.is_empty() {
+        let mut compiled_paths: IndexMap<String, Vec<String>> = IndexMap::new();
+        for (alias, targets) in &settings.paths {
+            compiled_paths.insert(alias.clone(), targets.clone());
+        }
+        swc_options.config.jsc.paths = compiled_paths;
+    }
+    if let Some(base_url) = &settings.base_url {
+        swc_options.config.jsc.base_url = base_url.clone();
+    }
+
+    Ok(settings)
+}
+
+fn parse_es_version(target: &str) -> Option<EsVersion> {
+    match target.to_ascii_lowercase().as_str() {
+        "es3" => Some(EsVersion::Es3),
+        "es5" => Some(EsVersion::Es5),
+        "es2015" | "es6" => Some(EsVersion::Es2015),
+        "es2016" => Some(EsVersion::Es2016),
+        "es2017" => Some(EsVersion::Es2017),
+        "es2018" => Some(EsVersion::Es2018),
+        "es2019" => Some(EsVersion::Es2019),
+        "es2020" => Some(EsVersion::Es2020),
+        "es2021" => Some(EsVersion::Es2021),
+        "es2022" => Some(EsVersion::Es2022),
+        "esnext" => Some(EsVersion::EsNext),
+        _ => None,
+    }
+}
+
+/// Resolves both relative imports (against the importing file) and bare specifiers (by walking
+/// `node_modules_path`, honoring `package.json` `exports`/`main` and the tsconfig `paths` aliases
+/// captured in `settings`) - the [`Resolve`] half of what `bundle()`'s `loader`/`hook` stubs used
+/// to leave as a no-op. Shares its on-disk probing and `package.json` handling with
+/// [`crate::dependencies::DependencyWatcher`], the other place this crate needs the same
+/// semantics, rather than re-deriving them.
+struct NodeModulesResolver {
+    node_modules_path: PathBuf,
+    settings: TsconfigSettings,
+}
+
+impl NodeModulesResolver {
+    fn new(node_modules_path: PathBuf, settings: TsconfigSettings) -> Self {
+        Self {
+            node_modules_path,
+            settings,
+        }
+    }
+
+    /// The directory non-relative specifiers and `paths` targets resolve against: `baseUrl` if the
+    /// tsconfig set one, otherwise the `node_modules_path`'s own parent (the project root).
+    fn base_dir(&self) -> PathBuf {
+        self.settings.base_url.clone().unwrap_or_else(|| {
+            self.node_modules_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| self.node_modules_path.clone())
+        })
+    }
+
+    fn resolve_via_paths(&self, module_specifier: &str) -> Option<PathBuf> {
+        let base_dir = self.base_dir();
+
+        for (alias, targets) in &self.settings.paths {
+            let prefix = alias.trim_end_matches('*');
+            if !module_specifier.starts_with(prefix) {
+                continue;
+            }
+
+            let remainder = module_specifier.trim_start_matches(prefix);
+            for target in targets {
+                let target_path = target.replace('*', remainder);
+                let candidate = base_dir.join(target_path);
+                if let Some(resolved) = resolve_on_disk(&candidate) {
+                    return Some(resolved.canonicalize().unwrap_or(resolved));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Resolves a bare specifier against the target package's own `package.json` (`exports`, then
+    /// `main`/`module`, then a plain index), the same semantics
+    /// [`crate::dependencies::DependencyWatcher::resolve_node_package`] applies, just rooted at
+    /// this bundler's own `node_modules_path` instead of a watched project root.
+    fn resolve_node_package(&self, module_specifier: &str) -> Option<PathBuf> {
+        let (package_name, subpath) = split_package_specifier(module_specifier);
+        let package_dir = self.node_modules_path.join(&package_name);
+        let package_json_path = package_dir.join("package.json");
+        let package_json: Value = fs::read_to_string(&package_json_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())?;
+
+        if let Some(exports) = package_json.get("exports") {
+            if let Some(resolved) = resolve_exports_map(exports, &subpath, &package_dir) {
+                return Some(resolve_candidate(&resolved));
+            }
+        }
+
+        if subpath.is_empty() {
+            for field in ["main", "module"] {
+                if let Some(entry) = package_json.get(field).and_then(Value::as_str) {
+                    if let Some(resolved) = resolve_on_disk(&package_dir.join(entry)) {
+                        return Some(resolved.canonicalize().unwrap_or(resolved));
+                    }
+                }
+            }
+            return Some(resolve_candidate(&package_dir));
+        }
+
+        Some(resolve_candidate(&package_dir.join(&subpath)))
+    }
+}
+
+impl Resolve for NodeModulesResolver {
+    fn resolve(&self, base: &FileName, module_specifier: &str) -> anyhow::Result<FileName> {
+        if module_specifier.starts_with('.') {
+            let base_dir = match base {
+                FileName::Real(path) => path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+            return Ok(FileName::Real(resolve_candidate(
+                &base_dir.join(module_specifier),
+            )));
+        }
+
+        if module_specifier.starts_with('/') {
+            return Ok(FileName::Real(resolve_candidate(&PathBuf::from(
+                module_specifier,
+            ))));
+        }
+
+        if let Some(resolved) = self.resolve_via_paths(module_specifier) {
+            return Ok(FileName::Real(resolved));
+        }
+
+        if let Some(resolved) = self.resolve_node_package(module_specifier) {
+            return Ok(FileName::Real(resolved));
+        }
+
+        // Nothing on disk matched - same "bare join, let the caller's own error surface it" fallback
+        // `DependencyWatcher::resolve_alias_import` uses once `package.json` lookup comes up empty.
+        Ok(FileName::Real(self.node_modules_path.join(module_specifier)))
+    }
+}
+
+/// Parses each module SWC's bundler asks for, choosing its syntax (plain/JSX, JS/TS) from the
+/// resolved file's own extension rather than always assuming TSX - a `.js` entry point pulling in
+/// a `.ts` dependency (or vice versa) previously would have been parsed with the wrong grammar.
+struct FsLoader;
+
+impl Load for FsLoader {
+    fn load(&self, file_name: &FileName) -> anyhow::Result<ModuleData> {
+        let path = match file_name {
+            FileName::Real(path) => path.clone(),
+            other => anyhow::bail!("Cannot load a non-file module: {:?}", other),
+        };
+
+        let cm: Arc<SourceMap> = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        let fm = cm.load_file(&path)?;
+
+        let syntax = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ts") => Syntax::Typescript(TsSyntax {
+                tsx: false,
+                ..Default::default()
+            }),
+            Some("tsx") => Syntax::Typescript(TsSyntax {
+                tsx: true,
+                ..Default::default()
+            }),
+            Some("jsx") => Syntax::Es(EsSyntax {
+                jsx: true,
+                ..Default::default()
+            }),
+            _ => Syntax::Es(EsSyntax {
+                jsx: false,
+                ..Default::default()
+            }),
+        };
+
+        let lexer = Lexer::new(syntax, Default::default(), StringInput::from(&*fm), None);
+        let mut parser = Parser::new_from(lexer);
+        let module = parser
+            .parse_module()
+            .map_err(|e| anyhow::anyhow!("Failed to parse module {:?}: {:?}", path, e))?;
+
+        Ok(ModuleData {
+            fm,
+            module,
+            helpers: Default::default(),
+        })
+    }
+}
+
 pub fn bundle(options: BundleOptions) -> anyhow::Result<Vec<BundleResult>> {
     // Create source map and error handler
     let cm: Arc<SourceMap> = Arc::new(SourceMap::new(FilePathMapping::empty()));
     let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(cm.clone()));
 
-    try_with_handler(cm.clone(), handler, |handler| {
-        // Configure SWC bundler options
+    try_with_handler(cm.clone(), handler, |_handler| {
         let mut swc_options = Options::default();
         swc_options.config.jsc.target = Some(EsVersion::Es2020);
-        
-        // Set environment variables
-        // Create bundler with custom module loader
-        let bundler = Bundler::new(
-            &cm,
-            loader,
-            None,
-            &swc_options,
-            None,
-            Box::new(hook),
+
+        // Unlike `crate::dependencies`'s tsconfig loader, this one doesn't walk a `"extends"`
+        // chain - a single `tsconfig.json` is read as-is. That loader's `deep_merge_json` is the
+        // tool to reach for if/when this one needs the same chain-following behavior.
+        let tsconfig_settings = match &options.tsconfig_path {
+            Some(tsconfig_path) => load_tsconfig(Path::new(tsconfig_path), &mut swc_options)?,
+            None => TsconfigSettings::default(),
+        };
+
+        let resolver = NodeModulesResolver::new(
+            PathBuf::from(&options.node_modules_path),
+            tsconfig_settings,
         );
 
-        // Process each entry point
-        let mut results = Vec::new();
-        for entry_point in options.entry_points {
-            let entry_path = Path::new(&entry_point);
-            let output_path = if let Some(ref outdir) = options.outdir {
-                PathBuf::from(outdir).join(entry_path.file_name().unwrap())
-            } else {
-                entry_path.with_extension("out")
-            };
+        let globals = Globals::new();
+        GLOBALS.set(&globals, || {
+            let bundler = Bundler::new(
+                &globals,
+                cm.clone(),
+                FsLoader,
+                resolver,
+                swc_bundler::Config::default(),
+                Box::new(NoopHook),
+            );
 
-            // Bundle the entry point
-            let bundle = bundler.bundle(entry_path)?;
-            
-            // Generate code and source map
-            let (code, map) = bundle.emit()?;
-
-            results.push(BundleResult {
-                code,
-                map: Some(map),
-                path: output_path,
-            });
-        }
+            let mut results = Vec::new();
+            for entry_point in &options.entry_points {
+                let entry_path = Path::new(entry_point);
+                let output_path = if let Some(ref outdir) = options.outdir {
+                    PathBuf::from(outdir).join(entry_path.file_name().unwrap())
+                } else {
+                    entry_path.with_extension("out")
+                };
 
-        Ok(results)
-    })
-}
+                let mut entries = std::collections::HashMap::new();
+                entries.insert(
+                    entry_path.file_stem().unwrap().to_string_lossy().to_string(),
+                    FileName::Real(entry_path.to_path_buf()),
+                );
+                let bundles = bundler.bundle(entries)?;
 
-// Custom module loader for resolving imports
-fn loader(path: &Path) -> anyhow::Result<ModuleData> {
-    let cm: Arc<SourceMap> = Arc::new(SourceMap::new(FilePathMapping::empty()));
-    
-    // Load file content
-    let fm = cm.load_file(path)?;
-    
-    // Parse as TypeScript/JSX
-    let lexer = Lexer::new(
-        Syntax::Typescript(TsConfig {
-            tsx: true,
-            ..Default::default()
-        }),
-        Default::default(),
-        StringInput::from(&*fm),
-        None,
-    );
-
-    let mut parser = Parser::new_from(lexer);
-    let module = parser.parse_module()?;
-
-    Ok(ModuleData {
-        fm,
-        module,
-        helpers: Default::default(),
+                for bundle in bundles {
+                    let mut buf = vec![];
+                    let mut raw_map = vec![];
+                    {
+                        let mut emitter = Emitter {
+                            cfg: swc_ecma_codegen::Config::default(),
+                            cm: cm.clone(),
+                            comments: None,
+                            wr: JsWriter::new(cm.clone(), "\n", &mut buf, Some(&mut raw_map)),
+                        };
+                        emitter.emit_module(&bundle.module)?;
+                    }
+
+                    let mut map_buf = vec![];
+                    cm.build_source_map(&raw_map).to_writer(&mut map_buf)?;
+
+                    results.push(BundleResult {
+                        code: String::from_utf8(buf)?,
+                        map: Some(String::from_utf8(map_buf)?),
+                        path: output_path.clone(),
+                    });
+                }
+            }
+
+            Ok(results)
+        })
     })
 }
 
-// Hook for module resolution and transformation
-fn hook(record: &mut ModuleRecord) -> anyhow::Result<()> {
-    // Here you can implement custom module resolution logic,
-    // like handling node_modules, aliases, etc.
-    Ok(())
+/// `bundle()` doesn't use SWC's `Hook` mechanism (injecting synthetic bindings like `__dirname`
+/// into a module) today, so this is a deliberate no-op rather than a real implementation - same
+/// honest gap the pre-existing `hook()` stub this replaces already had.
+struct NoopHook;
+
+impl swc_bundler::Hook for NoopHook {
+    fn get_import_bindings(
+        &self,
+        _span: swc_common::Span,
+        _module_record: &ModuleRecord,
+    ) -> Vec<swc_ecma_ast::KeyValueProp> {
+        Vec::new()
+    }
 }
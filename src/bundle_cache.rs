@@ -0,0 +1,384 @@
+use log::debug;
+use rustc_hash::FxHasher;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tempfile::NamedTempFile;
+
+use crate::bundle_common::{
+    bundle_common, BundleError, BundleMode, BundleResult, BundleResults, SourceMapMode,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedBundleResult {
+    script: String,
+    map: Option<String>,
+}
+
+impl From<&BundleResult> for CachedBundleResult {
+    fn from(result: &BundleResult) -> Self {
+        Self {
+            script: result.script.clone(),
+            map: result.map.clone(),
+        }
+    }
+}
+
+impl From<&CachedBundleResult> for BundleResult {
+    fn from(result: &CachedBundleResult) -> Self {
+        Self {
+            script: result.script.clone(),
+            map: result.map.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedEntry {
+    // `FxHasher` digest over the transitive local dependency closure's contents/mtimes plus
+    // every bundler-relevant input. A mismatch means at least one of those inputs changed.
+    key: u64,
+    entrypoints: HashMap<String, CachedBundleResult>,
+    extras: HashMap<String, CachedBundleResult>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RequestGraphCache {
+    // Keyed by the sorted, joined entrypoint paths so a differently-ordered call with the same
+    // inputs still lands on the same cache record.
+    entries: HashMap<String, CachedEntry>,
+}
+
+fn manifest_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("bundle_cache.json")
+}
+
+fn manifest_lock_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("bundle_cache.json.lock")
+}
+
+fn load_manifest(cache_dir: &Path) -> RequestGraphCache {
+    fs::read_to_string(manifest_path(cache_dir))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Writes `manifest` to `manifest_path(cache_dir)` without ever exposing a reader to a
+/// partially-written file: the bytes land in a sibling temp file first, then a single `rename`
+/// swaps it into place - the same idiom `src_go`'s `atomic_write::write_atomic` and
+/// `bundle_independent`'s `atomic_write` use for their own cache files.
+fn save_manifest(cache_dir: &Path, manifest: &RequestGraphCache) -> Result<(), BundleError> {
+    fs::create_dir_all(cache_dir).map_err(BundleError::IoError)?;
+    let contents = serde_json::to_string_pretty(manifest).map_err(|e| {
+        BundleError::OutputError(format!("Failed to serialize bundle cache: {}", e))
+    })?;
+
+    let mut temp_file = NamedTempFile::new_in(cache_dir).map_err(BundleError::IoError)?;
+    temp_file
+        .write_all(contents.as_bytes())
+        .map_err(BundleError::IoError)?;
+    temp_file
+        .persist(manifest_path(cache_dir))
+        .map_err(|e| BundleError::IoError(e.error))?;
+    Ok(())
+}
+
+/// How long [`ManifestLock::acquire`] will keep retrying before giving up. Manifest updates are a
+/// serialize-a-few-KB-and-rename operation, so a build that's actually holding the lock releases
+/// it almost immediately - this just bounds how long another build waits on a *stuck* holder
+/// before failing with an error. A lock file left behind by a crashed process is not removed.
+const MANIFEST_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An exclusive, filesystem-based advisory lock over `bundle_cache.json`, held for the
+/// read-modify-write span around a single cache entry update so two builds racing on the same
+/// manifest can't clobber each other's entries: `OpenOptions::create_new` atomically fails if the
+/// lock file already exists, so exactly one holder at a time can hold the lock regardless of
+/// which process or thread is asking.
+struct ManifestLock {
+    lock_path: PathBuf,
+}
+
+impl ManifestLock {
+    fn acquire(cache_dir: &Path) -> Result<Self, BundleError> {
+        fs::create_dir_all(cache_dir).map_err(BundleError::IoError)?;
+        let lock_path = manifest_lock_path(cache_dir);
+        let deadline = Instant::now() + MANIFEST_LOCK_TIMEOUT;
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&lock_path) {
+                Ok(_) => return Ok(Self { lock_path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if Instant::now() >= deadline {
+                        return Err(BundleError::BundlingError(format!(
+                            "Timed out waiting for bundle cache lock at {:?}",
+                            lock_path
+                        )));
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => return Err(BundleError::IoError(e)),
+            }
+        }
+    }
+}
+
+impl Drop for ManifestLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.lock_path);
+    }
+}
+
+/// Naively walks local (relative) import specifiers starting from `entry` to build the set of
+/// files whose contents feed the cache key. Bare specifiers (node_modules packages) are not
+/// followed - only the first-party source tree affects the key. `node_modules_path` and
+/// `tsconfig_path` enter the key as path strings only, so a package updated in place under
+/// `node_modules` does not change it.
+fn collect_local_dependencies(entry: &Path, visited: &mut HashSet<PathBuf>) {
+    let Ok(canonical) = entry.canonicalize() else {
+        return;
+    };
+    if !visited.insert(canonical.clone()) {
+        return;
+    }
+
+    let Ok(contents) = fs::read_to_string(&canonical) else {
+        return;
+    };
+
+    for line in contents.lines() {
+        let Some(spec) = extract_import_specifier(line) else {
+            continue;
+        };
+        if !spec.starts_with('.') {
+            continue;
+        }
+        let Some(parent) = canonical.parent() else {
+            continue;
+        };
+        let resolved = parent.join(&spec);
+        let candidates = [
+            resolved.clone(),
+            resolved.with_extension("ts"),
+            resolved.with_extension("tsx"),
+            resolved.with_extension("js"),
+            resolved.with_extension("jsx"),
+        ];
+        if let Some(found) = candidates.into_iter().find(|candidate| candidate.is_file()) {
+            collect_local_dependencies(&found, visited);
+        }
+    }
+}
+
+fn extract_import_specifier(line: &str) -> Option<String> {
+    let line = line.trim();
+    if !(line.starts_with("import ") || line.starts_with("export ") || line.contains(" from ")) {
+        return None;
+    }
+    let quote_start = line.find(['"', '\''])?;
+    let quote_char = line.as_bytes()[quote_start] as char;
+    let rest = &line[quote_start + 1..];
+    let quote_end = rest.find(quote_char)?;
+    Some(rest[..quote_end].to_string())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn hash_inputs(
+    entrypoint_paths: &[String],
+    mode: &BundleMode,
+    environment: &str,
+    node_modules_path: &str,
+    live_reload_port: Option<u16>,
+    tsconfig_path: &Option<String>,
+    minify: bool,
+) -> u64 {
+    let mut hasher = FxHasher::default();
+
+    format!("{:?}", mode).hash(&mut hasher);
+    environment.hash(&mut hasher);
+    node_modules_path.hash(&mut hasher);
+    live_reload_port.hash(&mut hasher);
+    tsconfig_path.hash(&mut hasher);
+    minify.hash(&mut hasher);
+
+    let mut visited = HashSet::new();
+    for entrypoint in entrypoint_paths {
+        collect_local_dependencies(Path::new(entrypoint), &mut visited);
+    }
+
+    let mut files: Vec<PathBuf> = visited.into_iter().collect();
+    files.sort();
+
+    for file in files {
+        file.to_string_lossy().hash(&mut hasher);
+        if let Ok(modified) = fs::metadata(&file).and_then(|metadata| metadata.modified()) {
+            if let Ok(since_epoch) = modified.duration_since(UNIX_EPOCH) {
+                since_epoch.as_nanos().hash(&mut hasher);
+            }
+        }
+        if let Ok(contents) = fs::read(&file) {
+            contents.hash(&mut hasher);
+        }
+    }
+
+    hasher.finish()
+}
+
+fn cache_slot(entrypoint_paths: &[String]) -> String {
+    let mut sorted = entrypoint_paths.to_vec();
+    sorted.sort();
+    sorted.join("|")
+}
+
+/// Same as [`bundle_common`], but checks a persistent on-disk cache first, modeled on Parcel's
+/// request-tracker graph: each cache entry's invalidation key folds together the transitive
+/// local dependency closure's contents/mtimes with every bundler-relevant input (mode,
+/// environment, `node_modules_path`, `live_reload_port`, `tsconfig_path`, `minify`). An exact key
+/// match returns the previously-written [`BundleResults`] without invoking Rolldown at all; a
+/// mismatch re-bundles and overwrites the cached entry for next time.
+#[allow(clippy::too_many_arguments)]
+pub fn bundle_common_cached(
+    entrypoint_paths: Vec<String>,
+    mode: BundleMode,
+    environment: String,
+    node_modules_path: String,
+    live_reload_port: Option<u16>,
+    tsconfig_path: Option<String>,
+    minify: bool,
+    cache_dir: PathBuf,
+) -> Result<BundleResults, BundleError> {
+    let key = hash_inputs(
+        &entrypoint_paths,
+        &mode,
+        &environment,
+        &node_modules_path,
+        live_reload_port,
+        &tsconfig_path,
+        minify,
+    );
+    let slot = cache_slot(&entrypoint_paths);
+    let manifest = load_manifest(&cache_dir);
+
+    if let Some(entry) = manifest.entries.get(&slot) {
+        if entry.key == key {
+            debug!("Bundle cache hit for {:?}", entrypoint_paths);
+            return Ok(BundleResults {
+                entrypoints: entry
+                    .entrypoints
+                    .iter()
+                    .map(|(name, result)| (name.clone(), result.into()))
+                    .collect(),
+                extras: entry
+                    .extras
+                    .iter()
+                    .map(|(name, result)| (name.clone(), result.into()))
+                    .collect(),
+                manifest: HashMap::new(),
+            });
+        }
+    }
+
+    debug!("Bundle cache miss for {:?}", entrypoint_paths);
+    let results = bundle_common(
+        entrypoint_paths,
+        mode,
+        environment,
+        node_modules_path,
+        live_reload_port,
+        tsconfig_path,
+        minify.into(),
+        false,
+        None,
+        SourceMapMode::External,
+        None,
+    )?;
+
+    let new_entry = CachedEntry {
+        key,
+        entrypoints: results
+            .entrypoints
+            .iter()
+            .map(|(name, result)| (name.clone(), result.into()))
+            .collect(),
+        extras: results
+            .extras
+            .iter()
+            .map(|(name, result)| (name.clone(), result.into()))
+            .collect(),
+    };
+
+    // Hold the lock only around the read-modify-write of the manifest itself, not the (much
+    // slower) bundle_common call above: re-load under the lock rather than reusing the `manifest`
+    // snapshot taken before bundling, so a concurrent build that finished and saved in the
+    // meantime has its entry merged in rather than clobbered.
+    let lock = ManifestLock::acquire(&cache_dir)?;
+    let mut manifest = load_manifest(&cache_dir);
+    manifest.entries.insert(slot, new_entry);
+    save_manifest(&cache_dir, &manifest)?;
+    drop(lock);
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_manifest_round_trips_and_leaves_no_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manifest = RequestGraphCache::default();
+        manifest.entries.insert(
+            "entry.tsx".to_string(),
+            CachedEntry {
+                key: 42,
+                entrypoints: HashMap::new(),
+                extras: HashMap::new(),
+            },
+        );
+
+        save_manifest(dir.path(), &manifest).unwrap();
+        let loaded = load_manifest(dir.path());
+
+        assert_eq!(loaded.entries.get("entry.tsx").unwrap().key, 42);
+
+        let remaining: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(remaining, vec!["bundle_cache.json".to_string()]);
+    }
+
+    #[test]
+    fn test_manifest_lock_is_exclusive_and_released_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let lock = ManifestLock::acquire(dir.path()).unwrap();
+        assert!(manifest_lock_path(dir.path()).exists());
+
+        // A second, concurrent acquire attempt must not succeed while the first lock is held -
+        // try the same `create_new` open `acquire` uses, rather than `acquire` itself, so the
+        // test doesn't wait out the real multi-second timeout.
+        let lock_path = manifest_lock_path(dir.path());
+        let second_attempt =
+            OpenOptions::new().write(true).create_new(true).open(&lock_path);
+        assert!(
+            second_attempt.is_err(),
+            "a second holder should not be able to acquire the lock file while the first is held"
+        );
+
+        drop(lock);
+        assert!(
+            !lock_path.exists(),
+            "dropping the lock should remove its lock file"
+        );
+
+        // Now that it's released, acquiring again should succeed immediately.
+        ManifestLock::acquire(dir.path()).unwrap();
+    }
+}
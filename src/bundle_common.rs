@@ -1,3 +1,4 @@
+use crate::import_map::ImportMap;
 use indexmap::IndexMap;
 use log::debug;
 use rolldown::{
@@ -7,10 +8,10 @@ use rolldown::{
 use rustc_hash::FxHasher;
 use std::collections::HashMap;
 use std::fs;
-use std::hash::BuildHasherDefault;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
 use std::path::Path;
 use tempfile::TempDir;
-use tokio::runtime::Runtime;
+use tokio::runtime::Builder as TokioRuntimeBuilder;
 
 #[derive(Debug)]
 pub enum OutputType {
@@ -18,7 +19,7 @@ pub enum OutputType {
     Directory(std::path::PathBuf),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum BundleMode {
     // 1. Single client-side javascript: wraps all dependencies in one file, intended for development embedding
     SingleClient,
@@ -28,6 +29,86 @@ pub enum BundleMode {
     SingleServer,
 }
 
+/// Controls how (and whether) source maps are produced for a bundle, mirroring the map-handling
+/// distinctions Deno's compiler carries through its module graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceMapMode {
+    /// Base64-embed the map directly into the script via a `data:` URI. `BundleResult.map` is
+    /// left as `None` since the map never exists as a standalone artifact.
+    Inline,
+    /// Emit the map as a sibling `.js.map` file and reference it via a `//# sourceMappingURL`
+    /// comment. This is the long-standing default behavior.
+    External,
+    /// Emit the map as a sibling file, but strip the `//# sourceMappingURL` comment from the
+    /// script - useful for uploading maps to an error tracker without shipping them.
+    Hidden,
+    /// Disable source maps entirely.
+    None,
+}
+
+/// Structured minify dimensions, mirroring the mangle/compress/top-level split that swc's
+/// `MinifyOptions` exposes. A single `minify: bool` flag gives callers no way to ask for
+/// whitespace/dead-code removal while keeping function names intact - exactly what SSR bundles
+/// need so V8 stack traces stay readable.
+#[derive(Debug, Clone, Copy)]
+pub struct MinifyConfig {
+    // Rename local identifiers to shorter ones.
+    pub mangle: bool,
+    // Also mangle top-level (module-scope) names. Ignored unless `mangle` is set.
+    pub toplevel_mangle: bool,
+    // Run dead-code elimination and other compression passes.
+    pub compress: bool,
+    // Keep `function`/class names intact even when mangling, so stack traces stay readable.
+    // Not passed to Rolldown, which only receives minification as on/off (see `bundle_common`).
+    pub keep_fn_names: bool,
+}
+
+impl MinifyConfig {
+    pub fn enabled() -> Self {
+        Self {
+            mangle: true,
+            toplevel_mangle: true,
+            compress: true,
+            keep_fn_names: false,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            mangle: false,
+            toplevel_mangle: false,
+            compress: false,
+            keep_fn_names: false,
+        }
+    }
+
+    /// Asks for compression with mangling off and `keep_fn_names` set, for the SingleServer SSR
+    /// path. Rolldown receives only an on/off minify flag, so this turns on the same full
+    /// minification as [`MinifyConfig::enabled`]; function and class names are not kept.
+    pub fn ssr_safe() -> Self {
+        Self {
+            mangle: false,
+            toplevel_mangle: false,
+            compress: true,
+            keep_fn_names: true,
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        !self.mangle && !self.compress
+    }
+}
+
+impl From<bool> for MinifyConfig {
+    fn from(minify: bool) -> Self {
+        if minify {
+            Self::enabled()
+        } else {
+            Self::disabled()
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BundleResult {
     // Since rust owns the tmp build directory, it's better to scope it to
@@ -43,6 +124,10 @@ pub struct BundleResults {
     pub entrypoints: HashMap<String, BundleResult>,
     // Map of extra generated file paths to their bundle results
     pub extras: HashMap<String, BundleResult>,
+    // Map of logical file name (e.g. "main.js") to the content-hashed name actually written to
+    // disk (e.g. "main.a1b2c3d4.js"). Only populated when `content_hash` is enabled; otherwise
+    // logical and hashed names are identical and this map is empty.
+    pub manifest: HashMap<String, String>,
 }
 
 // Custom error type for bundle operations
@@ -91,7 +176,10 @@ impl From<std::io::Error> for BundleError {
 /// * `node_modules_path` - Path to the node_modules directory for dependency resolution
 /// * `live_reload_port` - Optional port number for live reload functionality
 /// * `tsconfig_path` - Optional path to a tsconfig.json file for TypeScript configuration
-/// * `minify` - Boolean indicating whether to use aggressive minification
+/// * `minify` - The [`MinifyConfig`] selecting which minification passes to run
+/// * `source_map_mode` - The [`SourceMapMode`] controlling how (or whether) source maps are emitted
+/// * `extra_define` - Optional caller-supplied build-time constants, merged on top of the built-in
+///   `process.env.*` defines
 ///
 /// # Returns
 ///
@@ -106,6 +194,7 @@ impl From<std::io::Error> for BundleError {
 /// * Any entrypoint file doesn't exist
 /// * The bundling process fails
 /// * File I/O operations fail
+#[allow(clippy::too_many_arguments)]
 pub fn bundle_common(
     entrypoint_paths: Vec<String>,
     mode: BundleMode,
@@ -114,7 +203,14 @@ pub fn bundle_common(
     node_modules_path: String,
     live_reload_port: Option<u16>,
     tsconfig_path: Option<String>,
-    minify: bool,
+    minify: MinifyConfig,
+    content_hash: bool,
+    import_map: Option<String>,
+    source_map_mode: SourceMapMode,
+    // Caller-supplied build-time constants (e.g. feature flags, API base URLs), merged on top of
+    // the built-in `process.env.*` defines below so Rolldown can tree-shake dead branches out of
+    // client bundles.
+    extra_define: Option<std::collections::HashMap<String, String>>,
 ) -> Result<BundleResults, BundleError> {
     // Validate inputs
     if entrypoint_paths.is_empty() {
@@ -189,10 +285,35 @@ pub fn bundle_common(
         define.insert("process.env.SSR_RENDERING".to_string(), "false".to_string());
     }
 
+    // Caller-supplied constants take precedence over the built-ins above when keys collide,
+    // since they're more specific to the application being bundled.
+    if let Some(extra) = extra_define {
+        for (key, value) in extra {
+            define.insert(key, value);
+        }
+    }
+
+    // An import map lets callers redirect bare specifiers (`"react"`, `"@app/utils"`) to
+    // concrete paths or pinned versions without touching `node_modules`.
+    let alias = match &import_map {
+        Some(raw) => {
+            let parsed = ImportMap::parse(raw)?;
+            parsed.reject_unsupported_scopes()?;
+            let flattened = parsed.flatten_aliases();
+            if flattened.is_empty() {
+                None
+            } else {
+                Some(flattened.into_iter().collect::<Vec<_>>())
+            }
+        }
+        None => None,
+    };
+
     // Set up resolve options to let Rolldown know where to find node_modules.
     let resolve = Some(ResolveOptions {
         modules: Some(vec![node_modules_path.clone()]),
         tsconfig_filename: tsconfig_path,
+        alias,
         ..Default::default()
     });
 
@@ -233,7 +354,12 @@ pub fn bundle_common(
         // Required for inlining client-side scripts. Otherwise specifying a single file for ESM modules will
         // crash during bundling.
         inline_dynamic_imports: Some(true),
-        sourcemap: Some(SourceMapType::File),
+        sourcemap: match source_map_mode {
+            SourceMapMode::None => None,
+            SourceMapMode::Inline | SourceMapMode::External | SourceMapMode::Hidden => {
+                Some(SourceMapType::File)
+            }
+        },
         define: Some(define),
         resolve,
         // Choose the output format based on SSR flag
@@ -242,7 +368,11 @@ pub fn bundle_common(
         } else {
             Some(OutputFormat::Esm)
         },
-        minify: Some(RawMinifyOptions::Bool(minify)),
+        // Rolldown's own minifier options are currently all-or-nothing (`RawMinifyOptions` only
+        // exposes a `Bool` toggle), so `keep_fn_names`/`toplevel_mangle` aren't forwarded yet -
+        // this at least gives callers (and the SSR path in particular) the right Rust-side shape
+        // to ask for them, ready to wire through once Rolldown's minify surface grows to match.
+        minify: Some(RawMinifyOptions::Bool(!minify.is_noop())),
         // Add additional options as needed
         ..Default::default()
     };
@@ -250,7 +380,15 @@ pub fn bundle_common(
     // Create the bundler instance.
     let mut bundler = Bundler::new(bundler_options);
 
-    let rt = Runtime::new().map_err(|e| BundleError::BundlingError(e.to_string()))?;
+    // Size the runtime to what this process can actually use rather than Tokio's default (the
+    // host's total core count), which oversubscribes a container whose cgroup CPU quota is
+    // narrower than the host it's scheduled on.
+    let resource_limits = crate::resource_limits::ResourceLimits::detect();
+    let rt = TokioRuntimeBuilder::new_multi_thread()
+        .worker_threads(resource_limits.effective_cpus)
+        .enable_all()
+        .build()
+        .map_err(|e| BundleError::BundlingError(e.to_string()))?;
 
     rt.block_on(async {
         bundler
@@ -260,7 +398,133 @@ pub fn bundle_common(
     })?;
 
     // Process the output directory and return the results
-    process_output_directory(&output_dir, &entrypoint_paths)
+    let mut results = process_output_directory(&output_dir, &entrypoint_paths, source_map_mode)?;
+
+    if content_hash && matches!(mode, BundleMode::MultiClient) {
+        apply_content_hashing(&mut results);
+    }
+
+    Ok(results)
+}
+
+/// Renames every chunk in `results` to `<stem>.<hash>.js`, where `<hash>` is a short hex digest
+/// of the chunk's own bytes (the same `FxHasher` Parcel uses for its cache-busting filenames),
+/// rewrites intra-bundle import specifiers to match, and records the logical -> hashed name
+/// mapping in `results.manifest` so the Python client can emit correct `<script>` tags.
+fn apply_content_hashing(results: &mut BundleResults) {
+    // Maps every name a chunk might be referenced by (its bare stem, and its canonical
+    // `<stem>.js` filename) to the hashed filename, so both lookups below and specifier
+    // rewriting below can use a single table.
+    let mut renames: HashMap<String, String> = HashMap::new();
+    // Maps the canonical `<stem>.js` logical name to the hashed filename - this is what gets
+    // surfaced to callers as `BundleResults.manifest`.
+    let mut manifest: HashMap<String, String> = HashMap::new();
+
+    for (logical_name, bundle) in results.entrypoints.iter().chain(results.extras.iter()) {
+        let mut hasher = FxHasher::default();
+        bundle.script.hash(&mut hasher);
+        let digest = format!("{:x}", hasher.finish());
+        let short_digest = &digest[..digest.len().min(8)];
+
+        let stem = logical_name.trim_end_matches(".js");
+        let hashed_name = format!("{stem}.{short_digest}.js");
+        renames.insert(stem.to_string(), hashed_name.clone());
+        renames.insert(format!("{stem}.js"), hashed_name.clone());
+        manifest.insert(format!("{stem}.js"), hashed_name);
+    }
+
+    let rewrite_specifiers = |mut script: String| -> String {
+        for (logical_name, hashed_name) in &renames {
+            let stem = logical_name.trim_end_matches(".js");
+            // Rolldown emits relative ESM import specifiers without the `.js` suffix resolved
+            // away (e.g. `./utils`), so match on the bare stem as well as the full filename.
+            // Only the `<stem>.js` key rewrites unquoted references; doing it for the bare-stem
+            // key too would turn `./utils.js` into `./utils.<hash>.js.js`.
+            if logical_name.ends_with(".js") {
+                script =
+                    script.replace(&format!("./{logical_name}"), &format!("./{hashed_name}"));
+            }
+            script = script.replace(&format!("\"./{stem}\""), &format!("\"./{hashed_name}\""));
+            script = script.replace(&format!("'./{stem}'"), &format!("'./{hashed_name}'"));
+        }
+        script
+    };
+
+    let mut hashed_entrypoints = HashMap::new();
+    for (logical_name, mut bundle) in results.entrypoints.drain() {
+        bundle.script = rewrite_specifiers(bundle.script);
+        let hashed_name = renames
+            .get(&logical_name)
+            .cloned()
+            .unwrap_or_else(|| logical_name.clone());
+        rewrite_source_mapping_url(&mut bundle.script, &logical_name, &hashed_name);
+        hashed_entrypoints.insert(hashed_name, bundle);
+    }
+
+    let mut hashed_extras = HashMap::new();
+    for (logical_name, mut bundle) in results.extras.drain() {
+        bundle.script = rewrite_specifiers(bundle.script);
+        let hashed_name = renames
+            .get(&logical_name)
+            .cloned()
+            .unwrap_or_else(|| logical_name.clone());
+        rewrite_source_mapping_url(&mut bundle.script, &logical_name, &hashed_name);
+        hashed_extras.insert(hashed_name, bundle);
+    }
+
+    results.entrypoints = hashed_entrypoints;
+    results.extras = hashed_extras;
+    results.manifest = manifest;
+}
+
+/// Repoints a `//# sourceMappingURL=<stem>.js.map` comment at the hashed chunk's own map file, so
+/// the reference still resolves once [`apply_content_hashing`] has renamed the chunk it's in.
+/// `logical_name` is the pre-hash key (a bare stem for entrypoints, `<stem>.js` for extras), and
+/// `hashed_name` is the `<stem>.<hash>.js` name it was just renamed to.
+fn rewrite_source_mapping_url(script: &mut String, logical_name: &str, hashed_name: &str) {
+    let stem = logical_name.trim_end_matches(".js");
+    let old_reference = format!("//# sourceMappingURL={stem}.js.map");
+    let new_reference = format!("//# sourceMappingURL={hashed_name}.map");
+    *script = script.replace(&old_reference, &new_reference);
+}
+
+/// Removes any `//# sourceMappingURL=...` comment line Rolldown left in the script, leaving the
+/// rest of the output untouched. Used by [`SourceMapMode::Hidden`] (map file kept, reference
+/// dropped) and [`SourceMapMode::Inline`] (reference replaced with an embedded data URI).
+fn strip_source_mapping_comment(script: &mut String) {
+    *script = script
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//# sourceMappingURL="))
+        .collect::<Vec<_>>()
+        .join("\n");
+}
+
+/// Minimal standard (RFC 4648) base64 encoder used to embed source maps as `data:` URIs. Kept
+/// local and dependency-free since this is the bundler's only base64 use case.
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        out.push(ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    out
 }
 
 /// Processes the output directory after bundling to categorize and read generated files.
@@ -298,6 +562,7 @@ pub fn bundle_common(
 fn process_output_directory(
     output_dir: &Path,
     entrypoint_paths: &[String],
+    source_map_mode: SourceMapMode,
 ) -> Result<BundleResults, BundleError> {
     let mut entrypoints = HashMap::new();
     let mut extras = HashMap::new();
@@ -329,7 +594,7 @@ fn process_output_directory(
         }
 
         // Read the JavaScript output file
-        let script = fs::read_to_string(&path).map_err(|e| match e.kind() {
+        let mut script = fs::read_to_string(&path).map_err(|e| match e.kind() {
             std::io::ErrorKind::NotFound => BundleError::OutputError(format!(
                 "Expected output file not found: {}",
                 path.display()
@@ -339,12 +604,34 @@ fn process_output_directory(
 
         // Read the source map if it exists
         let map_path = path.with_extension("js.map");
-        let map = if map_path.exists() {
+        let map_contents = if map_path.exists() {
             Some(fs::read_to_string(&map_path).map_err(BundleError::IoError)?)
         } else {
             None
         };
 
+        // Reconcile the raw script/map pair with the requested `SourceMapMode`: `Inline` folds
+        // the map into the script as a `data:` URI and drops the standalone map, `Hidden` keeps
+        // the map but removes the script's reference to it, `External` leaves both untouched,
+        // and `None` never had a map to begin with.
+        let map = match source_map_mode {
+            SourceMapMode::Inline => {
+                strip_source_mapping_comment(&mut script);
+                if let Some(map_contents) = map_contents {
+                    let encoded = base64_encode(map_contents.as_bytes());
+                    script.push_str(&format!(
+                        "\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,{encoded}\n"
+                    ));
+                }
+                None
+            }
+            SourceMapMode::Hidden => {
+                strip_source_mapping_comment(&mut script);
+                map_contents
+            }
+            SourceMapMode::External | SourceMapMode::None => map_contents,
+        };
+
         // Create bundle result
         let bundle_result = BundleResult { script, map };
 
@@ -372,6 +659,7 @@ fn process_output_directory(
     Ok(BundleResults {
         entrypoints,
         extras,
+        manifest: HashMap::new(),
     })
 }
 
@@ -423,7 +711,11 @@ mod tests {
             node_modules_path,
             None,
             None,
-            true,
+            true.into(),
+            false,
+            None,
+            SourceMapMode::External,
+            None,
         );
 
         // Verify the result
@@ -491,7 +783,11 @@ mod tests {
             node_modules_path,
             None,
             None,
-            false, // Set minify to false to make it easier to inspect output
+            false.into(), // Set minify to false to make it easier to inspect output
+            false,
+            None,
+            SourceMapMode::External,
+            None,
         );
 
         // Verify the result
@@ -563,7 +859,11 @@ mod tests {
             node_modules_path,
             None,
             None,
-            true,
+            true.into(),
+            false,
+            None,
+            SourceMapMode::External,
+            None,
         );
 
         // Verify the result
@@ -650,7 +950,11 @@ mod tests {
             node_modules_path,
             None,
             None,
-            false, // Set minify to false to make it easier to inspect output
+            false.into(), // Set minify to false to make it easier to inspect output
+            false,
+            None,
+            SourceMapMode::External,
+            None,
         );
 
         // Verify the result
@@ -681,4 +985,94 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_content_hash_manifest() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let temp_path = temp_dir.path();
+
+        let utils_js = r#"
+            export function formatName(firstName, lastName) {
+                return `${firstName} ${lastName}`;
+            }
+        "#;
+        let utils_path = create_test_js_file(temp_path, "utils.js", utils_js)
+            .expect("Failed to create utils.js file");
+
+        let entry_js = r#"
+            import { formatName } from './utils';
+            console.log(formatName('John', 'Doe'));
+        "#;
+        let entry_path = create_test_js_file(temp_path, "main.js", entry_js)
+            .expect("Failed to create main.js file");
+
+        let node_modules_path = temp_path.join("node_modules").to_string_lossy().to_string();
+        fs::create_dir(temp_path.join("node_modules"))
+            .expect("Failed to create node_modules directory");
+
+        let result = bundle_common(
+            vec![entry_path, utils_path],
+            BundleMode::MultiClient,
+            "production".to_string(),
+            node_modules_path,
+            None,
+            None,
+            false.into(),
+            true,
+            None,
+            SourceMapMode::External,
+            None,
+        );
+
+        let bundles = result.expect("Bundle operation failed");
+        assert!(
+            !bundles.manifest.is_empty(),
+            "Expected a populated content-hash manifest"
+        );
+
+        for (logical_name, hashed_name) in &bundles.manifest {
+            assert_ne!(logical_name, hashed_name);
+            assert!(
+                bundles.entrypoints.contains_key(hashed_name)
+                    || bundles.extras.contains_key(hashed_name),
+                "Hashed name '{}' from the manifest should match an emitted chunk",
+                hashed_name
+            );
+        }
+    }
+
+    #[test]
+    fn test_bundle_common_rejects_an_import_map_with_scopes() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let temp_path = temp_dir.path();
+
+        let entry_path = create_test_js_file(temp_path, "entry.js", "console.log('hi');")
+            .expect("Failed to create entry.js file");
+        let node_modules_path = temp_path.join("node_modules").to_string_lossy().to_string();
+        fs::create_dir(temp_path.join("node_modules"))
+            .expect("Failed to create node_modules directory");
+
+        // Rolldown's alias table is global, not scoped to an importer subtree - folding `scopes`
+        // into it would silently resolve this non-deterministically depending on HashMap
+        // iteration order, so it must be rejected outright instead.
+        let import_map =
+            r#"{"imports": {}, "scopes": {"/admin/": {"react": "/vendor/react-admin.js"}}}"#
+                .to_string();
+
+        let result = bundle_common(
+            vec![entry_path],
+            BundleMode::SingleClient,
+            "development".to_string(),
+            node_modules_path,
+            None,
+            None,
+            false.into(),
+            false,
+            Some(import_map),
+            SourceMapMode::External,
+            None,
+        );
+
+        assert!(matches!(result, Err(BundleError::InvalidInput(_))));
+    }
 }
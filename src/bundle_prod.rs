@@ -3,10 +3,12 @@ use pyo3::types::{PyDict, PyList};
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
+use std::time::{Duration, Instant};
 use tempfile::TempDir;
 
 use crate::bundle_common::{self, BundleError, BundleMode};
 use crate::code_gen;
+use crate::thread_cpu;
 
 #[pyfunction]
 #[pyo3(
@@ -17,7 +19,11 @@ use crate::code_gen;
         minify,
         live_reload_import,
         is_server,
-        tsconfig_path = None
+        tsconfig_path = None,
+        content_hash = false,
+        import_map = None,
+        profile = false,
+        define = None
     )
 )]
 #[allow(clippy::too_many_arguments)]
@@ -30,6 +36,10 @@ pub fn compile_production_bundle(
     live_reload_import: String,
     is_server: bool,
     tsconfig_path: Option<String>,
+    content_hash: bool,
+    import_map: Option<String>,
+    profile: bool,
+    define: Option<std::collections::HashMap<String, String>>,
 ) -> PyResult<Py<PyDict>> {
     let bundle_output = compile_production_bundle_rust(
         &paths,
@@ -39,6 +49,10 @@ pub fn compile_production_bundle(
         &live_reload_import,
         is_server,
         tsconfig_path.as_deref(),
+        content_hash,
+        import_map,
+        profile,
+        define,
     )
     .map_err(|e| match e {
         BundleError::IoError(err) => pyo3::exceptions::PyIOError::new_err(err.to_string()),
@@ -52,7 +66,7 @@ pub fn compile_production_bundle(
 
     let result = PyDict::new(py);
 
-    let py_entrypoints = PyList::new(py, &bundle_output.entrypoints)?; // ❱ new signature
+    let py_entrypoints = PyList::new(py, &bundle_output.entrypoints)?;
     let py_entrypoint_maps = PyList::new(py, &bundle_output.entrypoint_maps)?;
 
     let py_supporting = PyDict::new(py);
@@ -65,10 +79,59 @@ pub fn compile_production_bundle(
         py_supporting_maps.set_item(filename, content)?;
     }
 
+    let py_manifest = PyDict::new(py);
+    for (logical_name, hashed_name) in bundle_output.manifest {
+        py_manifest.set_item(logical_name, hashed_name)?;
+    }
+
+    // Surfaces the cgroup CPU/memory ceiling this build ran under so the Python layer can warn
+    // when a minify-heavy production build is approaching the container's memory limit.
+    let py_resource_limits = PyDict::new(py);
+    py_resource_limits.set_item("effective_cpus", bundle_output.resource_limits.effective_cpus)?;
+    py_resource_limits.set_item(
+        "memory_limit_bytes",
+        bundle_output.resource_limits.memory_limit_bytes,
+    )?;
+
+    // Per-phase wall/CPU timings, only populated when `profile` was requested - sampling CPU
+    // time is an extra syscall per phase, so we skip it entirely rather than just discarding it
+    // when profiling is off.
+    let py_timings = match bundle_output.timings {
+        Some(timings) => {
+            let py_phases = PyList::empty(py);
+            for phase in &timings.phases {
+                let py_phase = PyDict::new(py);
+                py_phase.set_item("name", &phase.name)?;
+                py_phase.set_item("wall_time_ms", phase.wall_time.as_secs_f64() * 1_000.0)?;
+                py_phase.set_item(
+                    "cpu_time_ms",
+                    phase.cpu_time.map(|d| d.as_secs_f64() * 1_000.0),
+                )?;
+                py_phases.append(py_phase)?;
+            }
+
+            let py_totals = PyDict::new(py);
+            py_totals.set_item("wall_time_ms", timings.total_wall_time.as_secs_f64() * 1_000.0)?;
+            py_totals.set_item(
+                "cpu_time_ms",
+                timings.total_cpu_time.map(|d| d.as_secs_f64() * 1_000.0),
+            )?;
+
+            let py_timings = PyDict::new(py);
+            py_timings.set_item("phases", py_phases)?;
+            py_timings.set_item("totals", py_totals)?;
+            Some(py_timings)
+        }
+        None => None,
+    };
+
     result.set_item("entrypoints", &py_entrypoints)?;
     result.set_item("entrypoint_maps", &py_entrypoint_maps)?;
     result.set_item("supporting", &py_supporting)?;
     result.set_item("supporting_maps", &py_supporting_maps)?;
+    result.set_item("manifest", &py_manifest)?;
+    result.set_item("resource_limits", &py_resource_limits)?;
+    result.set_item("timings", py_timings)?;
 
     Ok(result.into())
 }
@@ -83,8 +146,29 @@ struct ProductionBundleOutput {
     supporting_maps: Vec<(String, String)>,
     #[allow(dead_code)]
     supporting_paths: Vec<String>,
+    manifest: std::collections::HashMap<String, String>,
+    resource_limits: crate::resource_limits::ResourceLimits,
+    timings: Option<BuildTimings>,
 }
 
+/// Wall/CPU time spent in one named phase of a production build.
+struct PhaseTiming {
+    name: String,
+    wall_time: Duration,
+    // `None` if the CPU-time syscall itself failed - profiling is still worth returning in that
+    // case, just without the CPU breakdown for that phase.
+    cpu_time: Option<Duration>,
+}
+
+/// Per-phase build profiling, populated only when the caller opts into `profile = true` so the
+/// extra CPU-time syscalls never run on a default build.
+struct BuildTimings {
+    phases: Vec<PhaseTiming>,
+    total_wall_time: Duration,
+    total_cpu_time: Option<Duration>,
+}
+
+#[allow(clippy::too_many_arguments)]
 fn compile_production_bundle_rust(
     paths: &[Vec<String>],
     node_modules_path: &str,
@@ -93,12 +177,21 @@ fn compile_production_bundle_rust(
     live_reload_import: &str,
     is_server: bool,
     tsconfig_path: Option<&str>,
+    content_hash: bool,
+    import_map: Option<String>,
+    profile: bool,
+    define: Option<std::collections::HashMap<String, String>>,
 ) -> Result<ProductionBundleOutput, BundleError> {
+    let build_start = Instant::now();
+    let mut phase_timings = Vec::new();
+
     let temp_dir = TempDir::new().map_err(BundleError::IoError)?;
     let temp_dir_path = temp_dir.path();
 
+    let phase = begin_phase(profile);
     let entrypoint_paths =
         create_synthetic_entrypoints_rust(temp_dir_path, paths, is_server, live_reload_import)?;
+    end_phase(&mut phase_timings, profile, "synthetic_entrypoints", phase);
 
     let bundle_mode = if is_server {
         BundleMode::SingleServer
@@ -106,6 +199,15 @@ fn compile_production_bundle_rust(
         BundleMode::MultiClient
     };
 
+    // The SSR bundle asks for `MinifyConfig::ssr_safe` (no mangling, function names kept), but
+    // Rolldown only receives an on/off minify flag, so it gets the same full minification as
+    // every other bundle - everywhere else gets the ordinary all-or-nothing `minify` behavior.
+    let minify_config = match (is_server, minify) {
+        (true, true) => bundle_common::MinifyConfig::ssr_safe(),
+        (_, enabled) => enabled.into(),
+    };
+
+    let phase = begin_phase(profile);
     let bundle_results = bundle_common::bundle_common(
         entrypoint_paths.clone(),
         bundle_mode,
@@ -113,8 +215,13 @@ fn compile_production_bundle_rust(
         node_modules_path.to_string(),
         None, // production: no live-reload port
         tsconfig_path.map(str::to_owned),
-        minify,
+        minify_config,
+        content_hash,
+        import_map,
+        bundle_common::SourceMapMode::External,
+        define,
     )?;
+    end_phase(&mut phase_timings, profile, "bundle", phase);
 
     let mut entrypoints = Vec::new();
     let mut entrypoint_maps = Vec::new();
@@ -124,18 +231,38 @@ fn compile_production_bundle_rust(
     let mut supporting_paths_raw: Vec<String> = Vec::new();
 
     for entrypoint_path in &entrypoint_paths {
+        let phase = begin_phase(profile);
         let file_stem = Path::new(entrypoint_path)
             .file_stem()
             .map(|s| s.to_string_lossy().to_string())
             .ok_or_else(|| BundleError::InvalidInput(format!("Invalid path: {entrypoint_path}")))?;
 
-        let bundle_result = bundle_results.entrypoints.get(&file_stem).ok_or_else(|| {
-            BundleError::OutputError(format!("No bundle result for entrypoint: {file_stem}"))
-        })?;
+        // Content hashing (when enabled) renames entrypoint keys from `<stem>` to
+        // `<stem>.<hash>.js`; look the hashed name up via the manifest before falling back to
+        // the unhashed stem so this lookup works either way.
+        let lookup_key = bundle_results
+            .manifest
+            .get(&format!("{file_stem}.js"))
+            .cloned()
+            .unwrap_or_else(|| file_stem.clone());
+
+        let bundle_result = bundle_results
+            .entrypoints
+            .get(&lookup_key)
+            .or_else(|| bundle_results.entrypoints.get(&file_stem))
+            .ok_or_else(|| {
+                BundleError::OutputError(format!("No bundle result for entrypoint: {file_stem}"))
+            })?;
 
         entrypoints.push(bundle_result.script.clone());
         entrypoint_maps.push(bundle_result.map.clone().unwrap_or_default());
         entrypoint_paths_raw.push(entrypoint_path.to_string());
+        end_phase(
+            &mut phase_timings,
+            profile,
+            &format!("extract:{file_stem}"),
+            phase,
+        );
     }
 
     for (filename, bundle_result) in bundle_results.extras {
@@ -146,6 +273,20 @@ fn compile_production_bundle_rust(
         supporting_paths_raw.push(filename.to_string());
     }
 
+    let timings = if profile {
+        Some(BuildTimings {
+            total_wall_time: build_start.elapsed(),
+            total_cpu_time: phase_timings
+                .iter()
+                .map(|phase| phase.cpu_time)
+                .collect::<Option<Vec<_>>>()
+                .map(|durations| durations.into_iter().sum()),
+            phases: phase_timings,
+        })
+    } else {
+        None
+    };
+
     Ok(ProductionBundleOutput {
         entrypoints,
         entrypoint_maps,
@@ -153,9 +294,47 @@ fn compile_production_bundle_rust(
         supporting,
         supporting_maps,
         supporting_paths: supporting_paths_raw,
+        manifest: bundle_results.manifest,
+        resource_limits: crate::resource_limits::ResourceLimits::detect(),
+        timings,
     })
 }
 
+/// Starts timing a phase, sampling the calling thread's CPU time too when `profile` is set.
+/// Returns `None` when profiling is off so [`end_phase`] can skip all measurement overhead.
+fn begin_phase(profile: bool) -> Option<(Instant, Option<Duration>)> {
+    profile.then(|| (Instant::now(), thread_cpu::current_thread_cpu_usage().ok()))
+}
+
+/// Closes out a phase started with [`begin_phase`], recording its wall-clock and (if available)
+/// CPU time into `timings`. A no-op when `profile` is false.
+fn end_phase(
+    timings: &mut Vec<PhaseTiming>,
+    profile: bool,
+    name: &str,
+    phase: Option<(Instant, Option<Duration>)>,
+) {
+    if !profile {
+        return;
+    }
+    let Some((started_at, cpu_start)) = phase else {
+        return;
+    };
+
+    let wall_time = started_at.elapsed();
+    let cpu_time = cpu_start.and_then(|start| {
+        thread_cpu::current_thread_cpu_usage()
+            .ok()
+            .map(|end| end.saturating_sub(start))
+    });
+
+    timings.push(PhaseTiming {
+        name: name.to_string(),
+        wall_time,
+        cpu_time,
+    });
+}
+
 fn create_synthetic_entrypoints_rust(
     temp_dir_path: &std::path::Path,
     paths: &[Vec<String>],
@@ -228,6 +407,10 @@ mod tests {
             live_reload_path.to_string_lossy().as_ref(),
             false,
             None,
+            false,
+            None,
+            false,
+            None,
         )
         .unwrap();
 
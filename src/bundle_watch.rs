@@ -0,0 +1,174 @@
+use log::{debug, warn};
+use notify::{RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::bundle_common::{bundle_common, BundleError, BundleMode, BundleResults, SourceMapMode};
+
+/// How long to coalesce bursts of filesystem events before triggering a rebuild pass.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(100);
+
+/// Starts a long-running watch over `watch_dirs`, re-bundling `entrypoint_paths` and pushing a
+/// fresh [`BundleResults`] down the returned channel every time a change is observed.
+///
+/// Following Deno's watch redesign, every entrypoint path is resolved against `initial_cwd` -
+/// the working directory captured once at the moment this function is called - rather than
+/// whatever the process's current directory happens to be at rebuild time. This keeps the
+/// watcher correct even if something elsewhere in the process calls `chdir` or a caller passed
+/// in a relative path backed by a temp directory that later gets recreated.
+///
+/// Each rebuild pass currently still goes through [`bundle_common`], which spins up its own
+/// `Bundler`/`Runtime` per call; reusing a single long-lived instance across rebuilds would need
+/// `bundle_common` itself restructured to hold onto its `Bundler`, which is out of scope here.
+#[allow(clippy::too_many_arguments)]
+pub fn bundle_watch(
+    entrypoint_paths: Vec<String>,
+    mode: BundleMode,
+    environment: String,
+    node_modules_path: String,
+    live_reload_port: Option<u16>,
+    tsconfig_path: Option<String>,
+    minify: bool,
+    watch_dirs: Vec<PathBuf>,
+) -> Result<Receiver<Result<BundleResults, BundleError>>, BundleError> {
+    let initial_cwd = std::env::current_dir().map_err(BundleError::IoError)?;
+    let resolved_entrypoints: Vec<String> = entrypoint_paths
+        .iter()
+        .map(|path| resolve_against(&initial_cwd, path))
+        .collect();
+
+    let (result_tx, result_rx) = mpsc::channel();
+    let (fs_tx, fs_rx) = mpsc::channel();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = fs_tx.send(event);
+        }
+    })
+    .map_err(|e| BundleError::BundlingError(format!("Failed to create filesystem watcher: {:?}", e)))?;
+
+    for dir in &watch_dirs {
+        watcher
+            .watch(dir, RecursiveMode::Recursive)
+            .map_err(|e| {
+                BundleError::BundlingError(format!("Failed to watch {:?}: {:?}", dir, e))
+            })?;
+    }
+
+    // Run an initial bundle immediately so the first consumer of the channel doesn't have to
+    // wait for a filesystem event before it sees any output.
+    let initial_result = bundle_common(
+        resolved_entrypoints.clone(),
+        mode,
+        environment.clone(),
+        node_modules_path.clone(),
+        live_reload_port,
+        tsconfig_path.clone(),
+        minify.into(),
+        false,
+        None,
+        SourceMapMode::External,
+        None,
+    );
+    let _ = result_tx.send(initial_result);
+
+    thread::spawn(move || {
+        // Keep the watcher (and thus the filesystem subscription) alive for the lifetime of
+        // this thread.
+        let _watcher = watcher;
+
+        loop {
+            let mut changed = false;
+
+            match fs_rx.recv_timeout(DEBOUNCE_WINDOW) {
+                Ok(_) => changed = true,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            // Coalesce any additional events arriving within the debounce window into the same
+            // rebuild pass.
+            let deadline = std::time::Instant::now() + DEBOUNCE_WINDOW;
+            while let Some(remaining) = deadline.checked_duration_since(std::time::Instant::now())
+            {
+                match fs_rx.recv_timeout(remaining) {
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+
+            if !changed {
+                continue;
+            }
+
+            debug!("Rebundling after filesystem change");
+            let result = bundle_common(
+                resolved_entrypoints.clone(),
+                mode,
+                environment.clone(),
+                node_modules_path.clone(),
+                live_reload_port,
+                tsconfig_path.clone(),
+                minify.into(),
+                false,
+                None,
+                SourceMapMode::External,
+                None,
+            );
+
+            if result_tx.send(result).is_err() {
+                // Receiver dropped - nothing left to stream results to, so stop watching.
+                break;
+            }
+        }
+    });
+
+    Ok(result_rx)
+}
+
+fn resolve_against(base: &Path, path: &str) -> String {
+    let candidate = Path::new(path);
+    if candidate.is_absolute() {
+        path.to_string()
+    } else {
+        base.join(candidate).to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bundle_watch_emits_initial_result() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let temp_path = temp_dir.path();
+
+        let entry_path = temp_path.join("entry.js");
+        fs::write(&entry_path, "export const value = 1;").unwrap();
+
+        let node_modules_path = temp_path.join("node_modules");
+        fs::create_dir(&node_modules_path).unwrap();
+
+        let rx = bundle_watch(
+            vec![entry_path.to_string_lossy().to_string()],
+            BundleMode::SingleClient,
+            "development".to_string(),
+            node_modules_path.to_string_lossy().to_string(),
+            None,
+            None,
+            false,
+            vec![temp_path.to_path_buf()],
+        )
+        .expect("Failed to start bundle_watch");
+
+        let first = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Expected an initial bundle result");
+        assert!(first.is_ok(), "Initial bundle failed: {:?}", first.err());
+    }
+}
@@ -0,0 +1,167 @@
+//! CPU-time watchdog for SSR renders - see [`crate::timeout`] for the wall-clock equivalent this
+//! complements. A render blocked on GC or contending with a busy host looks identical to an
+//! infinite loop under a wall-clock timer; sampling the render thread's *consumed CPU time*
+//! instead, via [`crate::thread_cpu::ThreadCpuHandle`], tells the two apart.
+
+use crate::thread_cpu::ThreadCpuHandle;
+use log::warn;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Soft/hard CPU-time budgets a render is allowed to consume, and how often to check them.
+/// Crossing `soft_budget` only logs a warning (once); crossing `hard_budget` runs the watch's
+/// `on_hard_budget` closure - expected to terminate the render's isolate - and stops polling.
+#[derive(Debug, Clone, Copy)]
+pub struct CpuWatchdog {
+    soft_budget: Duration,
+    hard_budget: Duration,
+    poll_interval: Duration,
+}
+
+impl CpuWatchdog {
+    pub fn new(soft_budget: Duration, hard_budget: Duration, poll_interval: Duration) -> Self {
+        Self {
+            soft_budget,
+            hard_budget,
+            poll_interval,
+        }
+    }
+
+    /// Starts watching the calling thread's CPU time until the returned [`WatchdogGuard`] is
+    /// dropped - typically by letting it fall out of scope at the end of a render, so every one
+    /// of a render's early-return error paths stops the monitor thread without having to remember
+    /// to do so explicitly. `on_hard_budget` runs at most once, from the monitor thread, the first
+    /// time accumulated CPU time reaches `hard_budget`.
+    pub fn watch<F>(&self, on_hard_budget: F) -> WatchdogGuard
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let handle = ThreadCpuHandle::current();
+        let done = Arc::new(AtomicBool::new(false));
+        let join_handle = self.spawn_monitor(handle, done.clone(), on_hard_budget);
+
+        WatchdogGuard {
+            done,
+            join_handle: Some(join_handle),
+        }
+    }
+
+    fn spawn_monitor<F>(
+        &self,
+        handle: ThreadCpuHandle,
+        done: Arc<AtomicBool>,
+        on_hard_budget: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let watchdog = *self;
+
+        thread::spawn(move || {
+            let mut warned_soft_budget = false;
+
+            while !done.load(Ordering::Relaxed) {
+                thread::sleep(watchdog.poll_interval);
+                if done.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let cpu_time = match handle.cpu_time() {
+                    Ok(cpu_time) => cpu_time,
+                    Err(err) => {
+                        warn!("CPU watchdog could not sample render thread CPU time: {err}");
+                        break;
+                    }
+                };
+
+                if cpu_time >= watchdog.hard_budget {
+                    warn!(
+                        "SSR render exceeded its hard CPU budget ({cpu_time:?} >= {:?}) - terminating",
+                        watchdog.hard_budget
+                    );
+                    on_hard_budget();
+                    break;
+                }
+
+                if !warned_soft_budget && cpu_time >= watchdog.soft_budget {
+                    warned_soft_budget = true;
+                    warn!(
+                        "SSR render exceeded its soft CPU budget ({cpu_time:?} >= {:?})",
+                        watchdog.soft_budget
+                    );
+                }
+            }
+        })
+    }
+}
+
+/// Stops a [`CpuWatchdog`]'s monitor thread on drop. Held alive for the duration of a render so
+/// every return path - success or any of its early-return errors - stops polling the same way.
+pub struct WatchdogGuard {
+    done: Arc<AtomicBool>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for WatchdogGuard {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::Relaxed);
+        if let Some(join_handle) = self.join_handle.take() {
+            let _ = join_handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool as StdAtomicBool;
+
+    #[test]
+    fn test_watchdog_does_not_trip_when_under_budget() {
+        let watchdog = CpuWatchdog::new(
+            Duration::from_secs(60),
+            Duration::from_secs(120),
+            Duration::from_millis(5),
+        );
+        let tripped = Arc::new(StdAtomicBool::new(false));
+        let tripped_clone = tripped.clone();
+
+        let guard = watchdog.watch(move || {
+            tripped_clone.store(true, Ordering::SeqCst);
+        });
+        thread::sleep(Duration::from_millis(50));
+        drop(guard);
+
+        assert!(!tripped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_watchdog_trips_hard_budget_on_cpu_bound_work() {
+        let watchdog = CpuWatchdog::new(
+            Duration::from_millis(5),
+            Duration::from_millis(10),
+            Duration::from_millis(5),
+        );
+        let tripped = Arc::new(StdAtomicBool::new(false));
+        let tripped_clone = tripped.clone();
+
+        let guard = watchdog.watch(move || {
+            tripped_clone.store(true, Ordering::SeqCst);
+        });
+
+        // Outrageously large amount of CPU-bound work - for all intents will keep the watched
+        // thread busy well past the hard budget above, so the monitor thread has something to
+        // catch it in the act of.
+        let mut acc: u64 = 0;
+        for n in 0..500_000_000u64 {
+            acc = acc.wrapping_add(n.wrapping_mul(2654435761));
+        }
+        std::hint::black_box(acc);
+
+        drop(guard);
+
+        assert!(tripped.load(Ordering::SeqCst));
+    }
+}
@@ -1,13 +1,22 @@
+use crate::import_map::ImportMap;
+use log::warn;
+use notify::{RecursiveMode, Watcher};
+use petgraph::algo::{tarjan_scc, toposort};
 use petgraph::graph::{DiGraph, EdgeIndex, NodeIndex};
 use petgraph::visit::{Dfs, EdgeRef, Walker};
 use petgraph::Direction;
 use serde_json::Value;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
 use swc_common::sync::Lrc;
 use swc_common::SourceMap;
-use swc_ecma_ast::{ImportDecl, Module};
+use swc_ecma_ast::{
+    Callee, CallExpr, ExportAll, Expr, ImportDecl, Lit, Module, NamedExport,
+};
 use swc_ecma_parser::{lexer::Lexer, EsSyntax, Parser, Syntax, TsSyntax};
 use swc_ecma_visit::{Visit, VisitWith};
 
@@ -19,6 +28,60 @@ impl<'a> Visit for DependencyExtractor {
     fn visit_import_decl(&mut self, import: &ImportDecl) {
         self.dependencies.push(import.src.value.to_string());
     }
+
+    fn visit_named_export(&mut self, export: &NamedExport) {
+        // `export { x } from "./a"` - re-exports without a `from` clause have no `src` and
+        // aren't a dependency edge.
+        if let Some(src) = &export.src {
+            self.dependencies.push(src.value.to_string());
+        }
+    }
+
+    fn visit_export_all(&mut self, export: &ExportAll) {
+        // `export * from "./b"`
+        self.dependencies.push(export.src.value.to_string());
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        // Dynamic `import("./c")`
+        if let Callee::Import(_) = &call.callee {
+            if let Some(specifier) = string_literal_arg(call) {
+                self.dependencies.push(specifier);
+            }
+        }
+
+        // CommonJS `require("./d")`
+        if let Callee::Expr(callee) = &call.callee {
+            if let Expr::Ident(ident) = &**callee {
+                if ident.sym == *"require" {
+                    if let Some(specifier) = string_literal_arg(call) {
+                        self.dependencies.push(specifier);
+                    }
+                }
+            }
+        }
+
+        call.visit_children_with(self);
+    }
+}
+
+/// Extracts a call expression's first argument as a string literal, or `None` if it's missing,
+/// computed, or of some other shape we can't statically resolve (e.g. `require(someVar)`).
+fn string_literal_arg(call: &CallExpr) -> Option<String> {
+    let arg = call.args.first()?;
+    match &*arg.expr {
+        Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+        _ => None,
+    }
+}
+
+/// The result of [`DependencyWatcher::topological_order`]: either a safe compilation order, or -
+/// if the graph isn't a DAG - the strongly-connected components responsible, so a caller can
+/// report the exact circular import chains instead of just failing.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TopoResult {
+    Ordered(Vec<PathBuf>),
+    Cycles(Vec<Vec<PathBuf>>),
 }
 
 pub struct DependencyWatcher {
@@ -26,16 +89,26 @@ pub struct DependencyWatcher {
     node_map: HashMap<PathBuf, NodeIndex>,
     root_dir: PathBuf,
     tsconfig: Option<Value>,
+    // Keyed by each package's own `package.json` path (not the bare package name), since a
+    // workspace can have the same package resolved from more than one `node_modules` directory.
+    // `None` means we already looked and there was no (or no parseable) `package.json` there.
+    package_json_cache: RefCell<HashMap<PathBuf, Option<Value>>>,
+    // The parsed import map alongside the directory it was loaded from, since relative map
+    // targets resolve against the map file's own location rather than `root_dir`.
+    import_map: Option<(ImportMap, PathBuf)>,
 }
 
 impl DependencyWatcher {
-    pub fn new(root_dir: PathBuf) -> Result<Self, String> {
+    pub fn new(root_dir: PathBuf, import_map_path: Option<PathBuf>) -> Result<Self, String> {
         let tsconfig = Self::parse_tsconfig(&root_dir)?;
+        let import_map = Self::parse_import_map(import_map_path)?;
         let mut watcher = DependencyWatcher {
             graph: DiGraph::new(),
             node_map: HashMap::new(),
             root_dir,
             tsconfig,
+            package_json_cache: RefCell::new(HashMap::new()),
+            import_map,
         };
         watcher.index_all_files()?;
         Ok(watcher)
@@ -43,15 +116,68 @@ impl DependencyWatcher {
 
     fn parse_tsconfig(root_dir: &Path) -> Result<Option<Value>, String> {
         let tsconfig_path = root_dir.join("tsconfig.json");
-        if tsconfig_path.exists() {
-            let tsconfig_content = fs::read_to_string(tsconfig_path)
-                .map_err(|e| format!("Failed to read tsconfig.json: {:?}", e))?;
-            serde_json::from_str(&tsconfig_content)
-                .map_err(|e| format!("Failed to parse tsconfig.json: {:?}", e))
-                .map(Some)
-        } else {
-            Ok(None)
+        if !tsconfig_path.exists() {
+            return Ok(None);
         }
+
+        let mut visited = HashSet::new();
+        Self::load_tsconfig_chain(&tsconfig_path, root_dir, &mut visited).map(Some)
+    }
+
+    /// Loads a single tsconfig file and, if it declares `"extends"`, recursively loads and
+    /// deep-merges the parent chain first so the requested file's own `compilerOptions` win over
+    /// whatever it inherits. `visited` guards against an `extends` cycle (a file that, directly or
+    /// transitively, extends itself) by tracking each config's canonicalized path.
+    fn load_tsconfig_chain(
+        path: &Path,
+        root_dir: &Path,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Result<Value, String> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if !visited.insert(canonical) {
+            return Err(format!(
+                "Circular tsconfig \"extends\" chain detected at {:?}",
+                path
+            ));
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read tsconfig {:?}: {:?}", path, e))?;
+        let mut config: Value = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse tsconfig {:?}: {:?}", path, e))?;
+
+        if let Some(extends) = config
+            .get("extends")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+        {
+            let current_dir = path.parent().unwrap_or(root_dir);
+            let parent_path = resolve_tsconfig_extends_path(current_dir, &extends, root_dir);
+            let mut parent_config = Self::load_tsconfig_chain(&parent_path, root_dir, visited)?;
+            deep_merge_json(&mut parent_config, &config);
+            config = parent_config;
+        }
+
+        Ok(config)
+    }
+
+    fn parse_import_map(
+        import_map_path: Option<PathBuf>,
+    ) -> Result<Option<(ImportMap, PathBuf)>, String> {
+        let Some(path) = import_map_path else {
+            return Ok(None);
+        };
+
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read import map: {:?}", e))?;
+        let map =
+            ImportMap::parse(&contents).map_err(|e| format!("Failed to parse import map: {e}"))?;
+        let map_dir = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        Ok(Some((map, map_dir)))
     }
 
     fn index_all_files(&mut self) -> Result<(), String> {
@@ -167,10 +293,12 @@ impl DependencyWatcher {
     ) -> Result<PathBuf, String> {
         if import_path.starts_with('.') {
             // Relative import
-            Ok(current_file.parent().unwrap().join(import_path))
+            let candidate = current_file.parent().unwrap().join(import_path);
+            Ok(resolve_candidate(&candidate))
         } else if import_path.starts_with('/') {
             // Absolute import
-            Ok(self.root_dir.join(import_path.strip_prefix('/').unwrap()))
+            let candidate = self.root_dir.join(import_path.strip_prefix('/').unwrap());
+            Ok(resolve_candidate(&candidate))
         } else {
             // Potential alias import
             self.resolve_alias_import(import_path)
@@ -179,22 +307,139 @@ impl DependencyWatcher {
 
     fn resolve_alias_import(&self, import_path: &str) -> Result<PathBuf, String> {
         if let Some(tsconfig) = &self.tsconfig {
+            let base_dir = self.ts_base_dir(tsconfig);
+
             if let Some(paths) = tsconfig["compilerOptions"]["paths"].as_object() {
                 for (alias, targets) in paths {
                     if import_path.starts_with(alias.trim_end_matches('*')) {
                         if let Some(target) = targets.as_array().and_then(|t| t.first()) {
-                            let target_path = target.as_str().unwrap().replace("*", "");
+                            let target_path = target.as_str().unwrap_or_default().replace("*", "");
                             let relative_path =
                                 import_path.trim_start_matches(alias.trim_end_matches('*'));
-                            return Ok(self.root_dir.join(target_path).join(relative_path));
+                            let candidate = base_dir.join(target_path).join(relative_path);
+                            return Ok(resolve_candidate(&candidate));
                         }
                     }
                 }
             }
+
+            // With no matching `paths` entry, a `baseUrl` still makes non-relative specifiers
+            // resolvable directly underneath it - TypeScript's "non-relative module resolution".
+            if tsconfig["compilerOptions"]["baseUrl"].as_str().is_some() {
+                if let Some(resolved) = resolve_on_disk(&base_dir.join(import_path)) {
+                    return Ok(resolved.canonicalize().unwrap_or(resolved));
+                }
+            }
+        }
+
+        if let Some(resolved) = self.resolve_via_import_map(import_path) {
+            return Ok(resolved);
         }
 
-        // If no alias found, assume it's a node_modules import
-        Ok(self.root_dir.join("node_modules").join(import_path))
+        // Otherwise, resolve it as a real Node package via its package.json (exports/main/module),
+        // falling back to a bare node_modules join plus on-disk probing if that package can't be
+        // found or parsed.
+        if let Some(resolved) = self.resolve_node_package(import_path) {
+            return Ok(resolved);
+        }
+
+        let candidate = self.root_dir.join("node_modules").join(import_path);
+        Ok(resolve_candidate(&candidate))
+    }
+
+    /// The directory `paths` targets and non-relative specifiers resolve against: `root_dir`
+    /// joined with `compilerOptions.baseUrl` if set, otherwise `root_dir` itself.
+    fn ts_base_dir(&self, tsconfig: &Value) -> PathBuf {
+        match tsconfig["compilerOptions"]["baseUrl"].as_str() {
+            Some(base_url) => self.root_dir.join(base_url),
+            None => self.root_dir.clone(),
+        }
+    }
+
+    /// Consults the optional import map (see [`crate::import_map::ImportMap`]) for a match on
+    /// `import_path`, preferring an exact key over the longest matching trailing-`/` prefix key -
+    /// mirroring the WICG import maps resolution algorithm. Returns `None` if no map is loaded or
+    /// nothing in it matches, leaving the caller to fall back to `node_modules` resolution.
+    fn resolve_via_import_map(&self, import_path: &str) -> Option<PathBuf> {
+        let (map, map_dir) = self.import_map.as_ref()?;
+        let aliases = map.flatten_aliases();
+
+        if let Some(target) = aliases.get(import_path) {
+            return Some(resolve_candidate(&resolve_import_map_target(
+                target, map_dir,
+            )));
+        }
+
+        let mut best: Option<(&str, &str)> = None;
+        for (specifier, target) in &aliases {
+            if specifier.ends_with('/') && import_path.starts_with(specifier.as_str()) {
+                if best.map_or(true, |(best_spec, _)| specifier.len() > best_spec.len()) {
+                    best = Some((specifier, target));
+                }
+            }
+        }
+
+        let (specifier, target) = best?;
+        let remainder = &import_path[specifier.len()..];
+        let mut expanded_target = target.to_string();
+        if !expanded_target.ends_with('/') {
+            expanded_target.push('/');
+        }
+        expanded_target.push_str(remainder);
+
+        Some(resolve_candidate(&resolve_import_map_target(
+            &expanded_target,
+            map_dir,
+        )))
+    }
+
+    /// Resolves a bare specifier (`"react"`, `"@scope/pkg/sub/path"`) against the target
+    /// package's own `package.json`: its `exports` map if present (matching `"."`, conditional
+    /// keys, and `./*` wildcard patterns), otherwise `main`/`module`, otherwise plain index
+    /// resolution of the package directory. Returns `None` if the package has no `package.json`
+    /// at all, leaving the caller to fall back to a bare node_modules join.
+    fn resolve_node_package(&self, import_path: &str) -> Option<PathBuf> {
+        let (package_name, subpath) = split_package_specifier(import_path);
+        let package_dir = self.root_dir.join("node_modules").join(&package_name);
+        let package_json = self.load_package_json(&package_dir)?;
+
+        if let Some(exports) = package_json.get("exports") {
+            if let Some(resolved) = resolve_exports_map(exports, &subpath, &package_dir) {
+                return Some(resolve_candidate(&resolved));
+            }
+        }
+
+        if subpath.is_empty() {
+            for field in ["main", "module"] {
+                if let Some(entry) = package_json.get(field).and_then(|v| v.as_str()) {
+                    if let Some(resolved) = resolve_on_disk(&package_dir.join(entry)) {
+                        return Some(resolved.canonicalize().unwrap_or(resolved));
+                    }
+                }
+            }
+            return Some(resolve_candidate(&package_dir));
+        }
+
+        Some(resolve_candidate(&package_dir.join(&subpath)))
+    }
+
+    /// Reads and parses `<package_dir>/package.json`, memoizing the result (including a parse or
+    /// read failure, as `None`) so repeated lookups of the same package during `index_all_files`
+    /// don't keep re-reading the file from disk.
+    fn load_package_json(&self, package_dir: &Path) -> Option<Value> {
+        let path = package_dir.join("package.json");
+
+        if let Some(cached) = self.package_json_cache.borrow().get(&path) {
+            return cached.clone();
+        }
+
+        let parsed = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok());
+        self.package_json_cache
+            .borrow_mut()
+            .insert(path, parsed.clone());
+        parsed
     }
 
     pub fn get_affected_roots(
@@ -240,6 +485,368 @@ impl DependencyWatcher {
 
         Ok(affected_roots)
     }
+
+    /// Returns a dependency-respecting compilation order for every indexed module (dependencies
+    /// before their dependents), or - if the graph contains an import cycle - the offending
+    /// strongly-connected component groups instead of failing. A lone node with a self-loop
+    /// (`a.ts` importing itself) counts as a one-element cycle; any other singleton component
+    /// is just a module with no cyclic imports and is not reported.
+    pub fn topological_order(&self) -> TopoResult {
+        match toposort(&self.graph, None) {
+            // Edges point from importer to imported, so `toposort` yields importers first;
+            // reverse it so every module comes after the modules it imports.
+            Ok(order) => TopoResult::Ordered(
+                order.into_iter().rev().map(|idx| self.graph[idx].clone()).collect(),
+            ),
+            Err(_) => {
+                let cycles: Vec<Vec<PathBuf>> = tarjan_scc(&self.graph)
+                    .into_iter()
+                    .filter(|component| {
+                        component.len() > 1
+                            || component
+                                .first()
+                                .is_some_and(|&idx| self.graph.contains_edge(idx, idx))
+                    })
+                    .map(|component| {
+                        component.into_iter().map(|idx| self.graph[idx].clone()).collect()
+                    })
+                    .collect();
+                TopoResult::Cycles(cycles)
+            }
+        }
+    }
+
+    /// Watches `root_dir` for filesystem changes, keeping the graph incrementally up to date and
+    /// calling `on_affected_roots` with the union of `roots` reachable from each changed batch.
+    ///
+    /// Bursts of events are debounced by [`WATCH_DEBOUNCE_WINDOW`], same as [`crate::bundle_watch`].
+    /// For each debounced batch: affected roots are computed first, while a to-be-deleted file's
+    /// edges are still in the graph to walk; modified/created files are then re-indexed via
+    /// [`Self::update_file`] and deleted files are dropped via [`Self::remove_file`]. Runs until
+    /// the underlying watcher errors or is dropped, so callers typically invoke this from a
+    /// dedicated thread.
+    pub fn watch_and_notify(
+        mut self,
+        roots: Vec<PathBuf>,
+        on_affected_roots: impl Fn(HashSet<PathBuf>),
+    ) -> Result<(), String> {
+        let root_dir = self.root_dir.clone();
+        let (fs_tx, fs_rx) = mpsc::channel();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let _ = fs_tx.send(event);
+            }
+        })
+        .map_err(|e| format!("Failed to create filesystem watcher: {:?}", e))?;
+
+        watcher
+            .watch(&root_dir, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {:?}: {:?}", root_dir, e))?;
+
+        loop {
+            let mut batch = Vec::new();
+
+            match fs_rx.recv_timeout(WATCH_DEBOUNCE_WINDOW) {
+                Ok(event) => batch.push(event),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            // Coalesce any additional events arriving within the debounce window into the same
+            // batch.
+            let deadline = Instant::now() + WATCH_DEBOUNCE_WINDOW;
+            while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+                match fs_rx.recv_timeout(remaining) {
+                    Ok(event) => batch.push(event),
+                    Err(_) => break,
+                }
+            }
+
+            let mut changed_paths = HashSet::new();
+            for event in batch {
+                for path in event.paths {
+                    if !self.is_ignored_path(&path) {
+                        changed_paths.insert(path);
+                    }
+                }
+            }
+
+            if changed_paths.is_empty() {
+                continue;
+            }
+
+            let mut affected = HashSet::new();
+            for path in &changed_paths {
+                let key = normalize_path(&path.to_path_buf());
+                if !self.node_map.contains_key(&key) {
+                    // Brand new file: nothing could have depended on it yet.
+                    continue;
+                }
+                match self.get_affected_roots(path, roots.clone()) {
+                    Ok(found) => affected.extend(found),
+                    Err(e) => warn!("Failed to compute affected roots for {:?}: {}", path, e),
+                }
+            }
+
+            for path in &changed_paths {
+                if path.is_file() {
+                    if let Err(e) = self.update_file(path) {
+                        warn!("Failed to index changed file {:?}: {}", path, e);
+                    }
+                } else {
+                    self.remove_file(path);
+                }
+            }
+
+            if !affected.is_empty() {
+                on_affected_roots(affected);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drops `file_path`'s node (and therefore its edges) from the graph, if it was indexed.
+    /// [`petgraph::graph::Graph::remove_node`] swaps the graph's last node into the freed slot, so
+    /// whichever path previously pointed at that last index is re-pointed at the freed one here to
+    /// keep `node_map` accurate.
+    fn remove_file(&mut self, file_path: &Path) {
+        let key = normalize_path(&file_path.to_path_buf());
+        let Some(node_index) = self.node_map.remove(&key) else {
+            return;
+        };
+
+        let last_index = NodeIndex::new(self.graph.node_count() - 1);
+        let moved_path = (last_index != node_index).then(|| self.graph[last_index].clone());
+
+        self.graph.remove_node(node_index);
+
+        if let Some(moved_path) = moved_path {
+            self.node_map
+                .insert(normalize_path(&moved_path), node_index);
+        }
+    }
+
+    /// Mirrors [`Self::index_directory`]'s ignore rules (`node_modules`, hidden directories) for a
+    /// single path coming off the filesystem watcher, additionally filtering out paths that aren't
+    /// one of the source extensions we index at all.
+    fn is_ignored_path(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root_dir).unwrap_or(path);
+        for component in relative.components() {
+            if let Component::Normal(name) = component {
+                let name = name.to_string_lossy();
+                if name == "node_modules" || name.starts_with('.') {
+                    return true;
+                }
+            }
+        }
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => !matches!(ext, "ts" | "tsx" | "js" | "jsx"),
+            None => true,
+        }
+    }
+}
+
+/// How long to coalesce bursts of filesystem events before applying them as one batch, mirroring
+/// [`crate::bundle_watch`]'s debounce window.
+const WATCH_DEBOUNCE_WINDOW: Duration = Duration::from_millis(100);
+
+/// Extensions tried against an extensionless candidate, in priority order - a TypeScript source
+/// shadows an equivalently-named compiled JS file, matching how bundlers typically resolve a
+/// mixed-source tree.
+const RESOLVABLE_EXTENSIONS: [&str; 6] = ["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Resolves a raw candidate path against what's actually on disk, mirroring Deno's sloppy-imports
+/// resolver: (1) an exact file wins outright; (2) otherwise each of [`RESOLVABLE_EXTENSIONS`] is
+/// tried as a suffix; (3) otherwise, if the candidate is itself a directory, its
+/// `index.{ts,tsx,js,jsx}` is tried. Returns `None` if nothing on disk matches - the target may
+/// simply not exist yet, a common transient state mid-edit.
+pub(crate) fn resolve_on_disk(candidate: &Path) -> Option<PathBuf> {
+    if candidate.is_file() {
+        return Some(candidate.to_path_buf());
+    }
+
+    for ext in RESOLVABLE_EXTENSIONS {
+        let with_ext = append_extension(candidate, ext);
+        if with_ext.is_file() {
+            return Some(with_ext);
+        }
+    }
+
+    if candidate.is_dir() {
+        for ext in ["ts", "tsx", "js", "jsx"] {
+            let index = candidate.join(format!("index.{ext}"));
+            if index.is_file() {
+                return Some(index);
+            }
+        }
+    }
+
+    None
+}
+
+fn append_extension(path: &Path, ext: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(ext);
+    path.with_file_name(file_name)
+}
+
+/// Resolves `candidate` against disk via [`resolve_on_disk`], canonicalizing the match so two
+/// different-looking specifiers for the same file (`./foo` vs `./foo.tsx`) dedupe to one graph
+/// node. Falls back to the bare, unresolved candidate when nothing on disk matches, so a module
+/// that hasn't been written yet still gets a (provisional) graph node rather than an error.
+pub(crate) fn resolve_candidate(candidate: &Path) -> PathBuf {
+    match resolve_on_disk(candidate) {
+        Some(resolved) => resolved.canonicalize().unwrap_or(resolved),
+        None => candidate.to_path_buf(),
+    }
+}
+
+/// Resolves a tsconfig `"extends"` value to the path of the config file it points at: a relative
+/// or absolute value is resolved against the extending file's own directory, while anything else
+/// is treated as a `node_modules` package reference. A value with no extension is assumed to name
+/// a directory (append `tsconfig.json`) or a bare module id missing its `.json` suffix.
+fn resolve_tsconfig_extends_path(current_dir: &Path, extends: &str, root_dir: &Path) -> PathBuf {
+    let base = if extends.starts_with('.') || extends.starts_with('/') {
+        current_dir.join(extends)
+    } else {
+        root_dir.join("node_modules").join(extends)
+    };
+
+    if base.extension().is_some() {
+        base
+    } else if base.is_dir() {
+        base.join("tsconfig.json")
+    } else {
+        base.with_extension("json")
+    }
+}
+
+/// Recursively merges `overlay` into `base`: matching object keys are merged recursively so only
+/// the leaves overlay actually sets are overridden, while any other value type is replaced
+/// outright. Used to apply a tsconfig's own `compilerOptions` on top of whatever it `extends`.
+pub(crate) fn deep_merge_json(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, overlay_value) in overlay_map {
+                match base_map.get_mut(key) {
+                    Some(base_value) => deep_merge_json(base_value, overlay_value),
+                    None => {
+                        base_map.insert(key.clone(), overlay_value.clone());
+                    }
+                }
+            }
+        }
+        (base_slot, overlay_value) => {
+            *base_slot = overlay_value.clone();
+        }
+    }
+}
+
+/// Resolves an import map target string to a path: an absolute target (`/vendor/react.js`) is
+/// rooted at the filesystem root, while anything else (including relative `./`/`../` targets) is
+/// resolved against `map_dir`, the directory containing the import map file itself.
+fn resolve_import_map_target(target: &str, map_dir: &Path) -> PathBuf {
+    match target.strip_prefix('/') {
+        Some(stripped) => PathBuf::from("/").join(stripped),
+        None => map_dir.join(target),
+    }
+}
+
+/// Splits a bare import specifier into its package name and the (possibly empty) subpath
+/// requested within it, respecting scoped packages (`@scope/name`) where the scope segment is
+/// part of the package name rather than the subpath.
+pub(crate) fn split_package_specifier(import_path: &str) -> (String, String) {
+    let mut parts = import_path.splitn(2, '/');
+    let first = parts.next().unwrap_or_default();
+
+    if let Some(scope) = first.strip_prefix('@') {
+        let _ = scope;
+        // Scoped package: the name is `@scope/name`, so consume one more segment.
+        let rest = parts.next().unwrap_or_default();
+        let mut rest_parts = rest.splitn(2, '/');
+        let name_tail = rest_parts.next().unwrap_or_default();
+        let subpath = rest_parts.next().unwrap_or_default();
+        return (format!("{first}/{name_tail}"), subpath.to_string());
+    }
+
+    let subpath = parts.next().unwrap_or_default();
+    (first.to_string(), subpath.to_string())
+}
+
+/// Resolves `subpath` (empty for the package root) against a `package.json` `exports` value,
+/// relative to `package_dir`. Handles the three shapes exports can take: a bare string (the
+/// package has only a root export), a conditions object (`"."`/`"./sub"` keys, optionally nested
+/// condition objects), and wildcard subpath patterns (`"./*"`). Returns `None` if nothing in the
+/// map matches - the caller should treat that the same as a missing `exports` field.
+pub(crate) fn resolve_exports_map(
+    exports: &Value,
+    subpath: &str,
+    package_dir: &Path,
+) -> Option<PathBuf> {
+    let key = if subpath.is_empty() {
+        ".".to_string()
+    } else {
+        format!("./{subpath}")
+    };
+
+    // A bare string or conditions object directly under `exports` is an implicit "." export.
+    if subpath.is_empty() {
+        if let Some(value) = resolve_export_value(exports) {
+            return Some(package_dir.join(value));
+        }
+    }
+
+    if let Some(map) = exports.as_object() {
+        if let Some(matched) = map.get(&key) {
+            if let Some(value) = resolve_export_value(matched) {
+                return Some(package_dir.join(value));
+            }
+        }
+
+        // Wildcard patterns, e.g. "./*" -> "./dist/*.js", longest prefix first.
+        let mut wildcard_keys: Vec<&String> = map
+            .keys()
+            .filter(|k| k.starts_with("./") && k.ends_with('*'))
+            .collect();
+        wildcard_keys.sort_by_key(|k| std::cmp::Reverse(k.len()));
+
+        for wildcard_key in wildcard_keys {
+            let prefix = &wildcard_key[..wildcard_key.len() - 1];
+            if let Some(matched_subpath) = key.strip_prefix(prefix) {
+                if let Some(template) = map.get(wildcard_key).and_then(resolve_export_value) {
+                    let expanded = template.replacen('*', matched_subpath, 1);
+                    return Some(package_dir.join(expanded));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Picks a concrete path out of an `exports` entry, which may be a plain string or a conditions
+/// object. Conditions are tried in the order Node itself documents as most specific first:
+/// `import`, `module`, `default`, falling back to `require` if that's all that's offered. Nested
+/// condition objects (e.g. `"import": { "default": "./x.js" }`) are resolved recursively.
+fn resolve_export_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(map) => {
+            for condition in ["import", "module", "default", "require"] {
+                if let Some(inner) = map.get(condition) {
+                    if let Some(resolved) = resolve_export_value(inner) {
+                        return Some(resolved);
+                    }
+                }
+            }
+            None
+        }
+        _ => None,
+    }
 }
 
 fn normalize_path(path: &PathBuf) -> PathBuf {
@@ -273,11 +880,13 @@ fn normalize_path(path: &PathBuf) -> PathBuf {
         }
     }
 
-    // Strip the file suffix
+    // Strip a known JS/TS source extension (only), so `button.module.ts` and `button.ts` stay
+    // distinct graph nodes instead of both collapsing to `button`.
     if let Some(file_name) = ret.file_name().and_then(|f| f.to_str()) {
-        if let Some(name_without_ext) = file_name.split('.').next() {
-            let new_path = ret.with_file_name(name_without_ext);
-            return new_path;
+        if let Some((stem, ext)) = file_name.rsplit_once('.') {
+            if RESOLVABLE_EXTENSIONS.contains(&ext) {
+                return ret.with_file_name(stem);
+            }
         }
     }
 
@@ -287,6 +896,8 @@ fn normalize_path(path: &PathBuf) -> PathBuf {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::thread;
+    use tempfile::TempDir;
 
     #[test]
     fn test_normalize_path() {
@@ -307,4 +918,455 @@ mod tests {
             PathBuf::from("/a/b/c")
         );
     }
+
+    #[test]
+    fn test_normalize_path_only_strips_known_extension() {
+        // A regression check: `button.module.ts` and `button.ts` must not collapse to the same
+        // node just because both contain a dot.
+        assert_eq!(
+            normalize_path(&PathBuf::from("/a/button.module.ts")),
+            PathBuf::from("/a/button.module")
+        );
+        assert_eq!(
+            normalize_path(&PathBuf::from("/a/button.ts")),
+            PathBuf::from("/a/button")
+        );
+        assert_ne!(
+            normalize_path(&PathBuf::from("/a/button.module.ts")),
+            normalize_path(&PathBuf::from("/a/button.ts"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_on_disk_tries_extensions_in_priority_order() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        fs::write(root.join("foo.tsx"), "export const Foo = 1;").unwrap();
+        fs::write(root.join("foo.js"), "module.exports = {};").unwrap();
+
+        let resolved = resolve_on_disk(&root.join("foo")).expect("Should resolve foo.tsx");
+        assert_eq!(resolved.file_name().unwrap(), "foo.tsx");
+    }
+
+    #[test]
+    fn test_resolve_on_disk_falls_back_to_directory_index() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        let component_dir = root.join("components");
+        fs::create_dir(&component_dir).unwrap();
+        fs::write(component_dir.join("index.ts"), "export const Index = 1;").unwrap();
+
+        let resolved =
+            resolve_on_disk(&component_dir).expect("Should resolve to the directory's index.ts");
+        assert_eq!(resolved.file_name().unwrap(), "index.ts");
+    }
+
+    #[test]
+    fn test_resolve_on_disk_returns_none_for_missing_module() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        assert!(resolve_on_disk(&root.join("does-not-exist")).is_none());
+    }
+
+    #[test]
+    fn test_split_package_specifier_unscoped() {
+        assert_eq!(
+            split_package_specifier("lodash/debounce"),
+            ("lodash".to_string(), "debounce".to_string())
+        );
+        assert_eq!(
+            split_package_specifier("react"),
+            ("react".to_string(), "".to_string())
+        );
+    }
+
+    #[test]
+    fn test_split_package_specifier_scoped() {
+        assert_eq!(
+            split_package_specifier("@scope/pkg/sub/path"),
+            ("@scope/pkg".to_string(), "sub/path".to_string())
+        );
+        assert_eq!(
+            split_package_specifier("@scope/pkg"),
+            ("@scope/pkg".to_string(), "".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_exports_map_exact_match() {
+        let exports = serde_json::json!({
+            ".": "./index.js",
+            "./button": "./src/button.js"
+        });
+        let package_dir = PathBuf::from("/pkg");
+
+        assert_eq!(
+            resolve_exports_map(&exports, "", &package_dir),
+            Some(PathBuf::from("/pkg/index.js"))
+        );
+        assert_eq!(
+            resolve_exports_map(&exports, "button", &package_dir),
+            Some(PathBuf::from("/pkg/src/button.js"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_exports_map_conditional() {
+        let exports = serde_json::json!({
+            ".": {
+                "import": "./esm/index.js",
+                "require": "./cjs/index.js"
+            }
+        });
+        let package_dir = PathBuf::from("/pkg");
+
+        assert_eq!(
+            resolve_exports_map(&exports, "", &package_dir),
+            Some(PathBuf::from("/pkg/esm/index.js"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_exports_map_wildcard() {
+        let exports = serde_json::json!({
+            "./*": "./dist/*.js"
+        });
+        let package_dir = PathBuf::from("/pkg");
+
+        assert_eq!(
+            resolve_exports_map(&exports, "utils/format", &package_dir),
+            Some(PathBuf::from("/pkg/dist/utils/format.js"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_node_package_uses_package_json_main() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        let package_dir = root.join("node_modules").join("some-lib");
+        fs::create_dir_all(&package_dir).unwrap();
+        fs::write(
+            package_dir.join("package.json"),
+            r#"{"name": "some-lib", "main": "lib/index.js"}"#,
+        )
+        .unwrap();
+        fs::create_dir(package_dir.join("lib")).unwrap();
+        fs::write(package_dir.join("lib").join("index.js"), "module.exports = {};").unwrap();
+
+        let watcher = DependencyWatcher {
+            graph: DiGraph::new(),
+            node_map: HashMap::new(),
+            root_dir: root.to_path_buf(),
+            tsconfig: None,
+            package_json_cache: RefCell::new(HashMap::new()),
+            import_map: None,
+        };
+
+        let resolved = watcher
+            .resolve_node_package("some-lib")
+            .expect("Should resolve via package.json main");
+        assert_eq!(resolved.file_name().unwrap(), "index.js");
+    }
+
+    #[test]
+    fn test_resolve_node_package_returns_none_without_package_json() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        let watcher = DependencyWatcher {
+            graph: DiGraph::new(),
+            node_map: HashMap::new(),
+            root_dir: root.to_path_buf(),
+            tsconfig: None,
+            package_json_cache: RefCell::new(HashMap::new()),
+            import_map: None,
+        };
+
+        assert!(watcher.resolve_node_package("missing-lib").is_none());
+    }
+
+    fn watcher_with_import_map(root: &Path, map: ImportMap, map_dir: PathBuf) -> DependencyWatcher {
+        DependencyWatcher {
+            graph: DiGraph::new(),
+            node_map: HashMap::new(),
+            root_dir: root.to_path_buf(),
+            tsconfig: None,
+            package_json_cache: RefCell::new(HashMap::new()),
+            import_map: Some((map, map_dir)),
+        }
+    }
+
+    #[test]
+    fn test_resolve_via_import_map_exact_match() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+        fs::write(root.join("react.js"), "module.exports = {};").unwrap();
+
+        let map = ImportMap::parse(r#"{"imports": {"react": "./react.js"}}"#).unwrap();
+        let watcher = watcher_with_import_map(root, map, root.to_path_buf());
+
+        let resolved = watcher
+            .resolve_via_import_map("react")
+            .expect("Should resolve exact match");
+        assert_eq!(resolved.file_name().unwrap(), "react.js");
+    }
+
+    #[test]
+    fn test_resolve_via_import_map_prefers_longest_prefix() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/utils/format")).unwrap();
+        fs::write(
+            root.join("src/utils/format/index.ts"),
+            "export const x = 1;",
+        )
+        .unwrap();
+
+        let map = ImportMap::parse(
+            r#"{"imports": {"utils/": "./src/utils/", "utils/format/": "./src/utils/format/"}}"#,
+        )
+        .unwrap();
+        let watcher = watcher_with_import_map(root, map, root.to_path_buf());
+
+        let resolved = watcher
+            .resolve_via_import_map("utils/format/index")
+            .expect("Should resolve via the longest matching prefix");
+        assert_eq!(resolved.file_name().unwrap(), "index.ts");
+    }
+
+    #[test]
+    fn test_resolve_via_import_map_returns_none_without_map() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        let watcher = DependencyWatcher {
+            graph: DiGraph::new(),
+            node_map: HashMap::new(),
+            root_dir: root.to_path_buf(),
+            tsconfig: None,
+            package_json_cache: RefCell::new(HashMap::new()),
+            import_map: None,
+        };
+
+        assert!(watcher.resolve_via_import_map("react").is_none());
+    }
+
+    #[test]
+    fn test_deep_merge_json_child_wins_on_conflict() {
+        let mut base = serde_json::json!({
+            "compilerOptions": {"baseUrl": ".", "strict": true},
+            "include": ["base-only"]
+        });
+        let overlay = serde_json::json!({
+            "compilerOptions": {"strict": false, "paths": {"@/*": ["./src/*"]}}
+        });
+
+        deep_merge_json(&mut base, &overlay);
+
+        assert_eq!(base["compilerOptions"]["baseUrl"], "."); // kept from base
+        assert_eq!(base["compilerOptions"]["strict"], false); // overlay wins
+        assert_eq!(base["compilerOptions"]["paths"]["@/*"][0], "./src/*");
+        assert_eq!(base["include"][0], "base-only");
+    }
+
+    #[test]
+    fn test_parse_tsconfig_follows_extends_chain() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        fs::write(
+            root.join("tsconfig.base.json"),
+            r#"{"compilerOptions": {"baseUrl": ".", "strict": true}}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{"extends": "./tsconfig.base.json", "compilerOptions": {"paths": {"@/*": ["./src/*"]}}}"#,
+        )
+        .unwrap();
+
+        let tsconfig = DependencyWatcher::parse_tsconfig(root)
+            .expect("Should parse successfully")
+            .expect("Should find a tsconfig");
+
+        assert_eq!(tsconfig["compilerOptions"]["baseUrl"], ".");
+        assert_eq!(tsconfig["compilerOptions"]["strict"], true);
+        assert_eq!(tsconfig["compilerOptions"]["paths"]["@/*"][0], "./src/*");
+    }
+
+    #[test]
+    fn test_parse_tsconfig_detects_extends_cycle() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{"extends": "./tsconfig.other.json"}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("tsconfig.other.json"),
+            r#"{"extends": "./tsconfig.json"}"#,
+        )
+        .unwrap();
+
+        assert!(DependencyWatcher::parse_tsconfig(root).is_err());
+    }
+
+    #[test]
+    fn test_resolve_alias_import_uses_base_url_for_non_relative_specifier() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        fs::create_dir_all(root.join("src/components")).unwrap();
+        fs::write(
+            root.join("src/components/button.tsx"),
+            "export const Button = 1;",
+        )
+        .unwrap();
+
+        let tsconfig = serde_json::json!({"compilerOptions": {"baseUrl": "./src"}});
+        let watcher = DependencyWatcher {
+            graph: DiGraph::new(),
+            node_map: HashMap::new(),
+            root_dir: root.to_path_buf(),
+            tsconfig: Some(tsconfig),
+            package_json_cache: RefCell::new(HashMap::new()),
+            import_map: None,
+        };
+
+        let resolved = watcher
+            .resolve_alias_import("components/button")
+            .expect("Should resolve via baseUrl");
+        assert_eq!(resolved.file_name().unwrap(), "button.tsx");
+    }
+
+    #[test]
+    fn test_remove_file_reindexes_swapped_node() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        fs::write(root.join("a.ts"), "import './b';").unwrap();
+        fs::write(root.join("b.ts"), "export const b = 1;").unwrap();
+
+        let mut watcher = DependencyWatcher::new(root.to_path_buf(), None)
+            .expect("Failed to build watcher");
+
+        let b_key = normalize_path(&root.join("b.ts"));
+        assert!(watcher.node_map.contains_key(&b_key));
+
+        watcher.remove_file(&root.join("b.ts"));
+
+        assert!(!watcher.node_map.contains_key(&b_key));
+        // Every remaining path's recorded index should still point at its own node. Keys are
+        // normalized (extension stripped) while node weights keep the raw path.
+        for (path, &index) in &watcher.node_map {
+            assert_eq!(&normalize_path(&watcher.graph[index]), path);
+        }
+    }
+
+    #[test]
+    fn test_is_ignored_path_skips_node_modules_and_non_source_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path().to_path_buf();
+
+        let watcher = DependencyWatcher {
+            graph: DiGraph::new(),
+            node_map: HashMap::new(),
+            root_dir: root.clone(),
+            tsconfig: None,
+            package_json_cache: RefCell::new(HashMap::new()),
+            import_map: None,
+        };
+
+        assert!(watcher.is_ignored_path(&root.join("node_modules/lib/index.ts")));
+        assert!(watcher.is_ignored_path(&root.join(".git/HEAD")));
+        assert!(watcher.is_ignored_path(&root.join("README.md")));
+        assert!(!watcher.is_ignored_path(&root.join("src/app.tsx")));
+    }
+
+    #[test]
+    fn test_watch_and_notify_reports_affected_roots_on_change() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path().to_path_buf();
+
+        let entry_path = root.join("entry.ts");
+        let dep_path = root.join("dep.ts");
+        fs::write(&entry_path, "import './dep';").unwrap();
+        fs::write(&dep_path, "export const value = 1;").unwrap();
+
+        let watcher =
+            DependencyWatcher::new(root.clone(), None).expect("Failed to build watcher");
+
+        let (tx, rx) = mpsc::channel();
+        let roots = vec![normalize_path(&entry_path)];
+        thread::spawn(move || {
+            let _ = watcher.watch_and_notify(roots, move |affected| {
+                let _ = tx.send(affected);
+            });
+        });
+
+        // Give the watcher a moment to finish registering before triggering a change, then touch
+        // the dependency so it ripples up to the entrypoint.
+        thread::sleep(Duration::from_millis(200));
+        fs::write(&dep_path, "export const value = 2;").unwrap();
+
+        let affected = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Expected an affected-roots notification");
+        // Affected roots are reported as the graph's own (un-normalized) node paths.
+        assert!(affected.contains(&entry_path));
+    }
+
+    #[test]
+    fn test_topological_order_orders_dependencies_before_dependents() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        fs::write(root.join("a.ts"), "import './b';").unwrap();
+        fs::write(root.join("b.ts"), "import './c';").unwrap();
+        fs::write(root.join("c.ts"), "export const c = 1;").unwrap();
+
+        let watcher =
+            DependencyWatcher::new(root.to_path_buf(), None).expect("Failed to build watcher");
+
+        let order = match watcher.topological_order() {
+            TopoResult::Ordered(order) => order,
+            TopoResult::Cycles(cycles) => panic!("Expected no cycles, got {:?}", cycles),
+        };
+
+        let a_pos = order.iter().position(|p| p.ends_with("a.ts")).unwrap();
+        let b_pos = order.iter().position(|p| p.ends_with("b.ts")).unwrap();
+        let c_pos = order.iter().position(|p| p.ends_with("c.ts")).unwrap();
+
+        // `a` depends on `b` depends on `c`, so `c` has to be built first and `a` last.
+        assert!(c_pos < b_pos);
+        assert!(b_pos < a_pos);
+    }
+
+    #[test]
+    fn test_topological_order_reports_cycle() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let root = temp_dir.path();
+
+        fs::write(root.join("a.ts"), "import './b';").unwrap();
+        fs::write(root.join("b.ts"), "import './a';").unwrap();
+
+        let watcher =
+            DependencyWatcher::new(root.to_path_buf(), None).expect("Failed to build watcher");
+
+        let cycles = match watcher.topological_order() {
+            TopoResult::Cycles(cycles) => cycles,
+            TopoResult::Ordered(order) => panic!("Expected a cycle, got order {:?}", order),
+        };
+
+        assert!(cycles
+            .iter()
+            .any(|cycle| cycle.iter().any(|p| p.ends_with("a.ts"))
+                && cycle.iter().any(|p| p.ends_with("b.ts"))));
+    }
 }
\ No newline at end of file
@@ -1,10 +1,67 @@
 use std::error::Error;
 use std::fmt;
 
+/// A single frame of a `JsError`'s stack trace, mirroring deno_core's `JsStackFrame` - one entry
+/// per `at ...` line V8's `v8::StackTrace` API can decompose a thrown exception's trace into.
+/// Any field may be absent: V8 doesn't always know a frame's function name (anonymous functions),
+/// and native/synthetic frames have no script to attribute a file name to.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JsStackFrame {
+    pub function_name: Option<String>,
+    pub file_name: Option<String>,
+    pub line: Option<i32>,
+    pub column: Option<i32>,
+}
+
+/// A V8 exception, structured instead of flattened to a string - mirrors deno_core's `JsError`.
+/// `class_name` is the thrown value's constructor name (e.g. `TypeError`), `None` for thrown
+/// non-`Error` values (e.g. `throw "boom"`). `frames` is empty when V8 has no stack trace to offer.
+///
+/// `message` already holds the same flattened string this crate has always surfaced through
+/// `AppError::V8ExceptionError` (built by `Ssr::extract_exception_message`), so `Display`
+/// reproduces it exactly - existing callers that only care about the string keep working
+/// unchanged. `class_name`/`frames` are there for callers (namely the PyO3 boundary) that want to
+/// classify the failure or walk its frames instead of pattern-matching a string.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JsError {
+    pub class_name: Option<String>,
+    pub message: String,
+    pub frames: Vec<JsStackFrame>,
+    /// The unremapped V8 stack trace, still pointing into bundled output - only populated when
+    /// `MOUNTAINEER_SSR_RAW_STACK` is set. See `Ssr::build_js_error`.
+    pub raw_stack: Option<String>,
+}
+
+impl fmt::Display for JsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Lets every existing `AppError::V8ExceptionError("...".into())` / `V8ExceptionError(format!(...))`
+/// call site keep constructing a plain, frame-less `JsError` without change.
+impl From<String> for JsError {
+    fn from(message: String) -> Self {
+        JsError {
+            class_name: None,
+            message,
+            frames: Vec::new(),
+            raw_stack: None,
+        }
+    }
+}
+
+impl From<&str> for JsError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum AppError {
-    V8ExceptionError(String),
+    V8ExceptionError(JsError),
     HardTimeoutError(String),
+    SoftTimeoutError(String),
 }
 
 impl fmt::Display for AppError {
@@ -12,6 +69,7 @@ impl fmt::Display for AppError {
         match *self {
             AppError::V8ExceptionError(ref err) => write!(f, "V8 Exception Error: {}", err),
             AppError::HardTimeoutError(ref err) => write!(f, "Hard Timeout Error: {}", err),
+            AppError::SoftTimeoutError(ref err) => write!(f, "Soft Timeout Error: {}", err),
         }
     }
 }
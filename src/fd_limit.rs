@@ -0,0 +1,87 @@
+//! Raises the process's soft `RLIMIT_NOFILE` toward its hard limit before a large parallel
+//! bundling run. [`crate::bundle_independent`]'s worker pool (chunk3-2) can easily have many
+//! groups with open source/node_modules files in flight at once, which on macOS's stingy default
+//! soft limit (often 256) surfaces as a spurious `BundleError::IoError` rather than a real
+//! bundling problem. The raise is process-lifetime - nothing is restored once the bundling run
+//! finishes, since a higher descriptor ceiling is always safe to keep around.
+
+#[cfg(unix)]
+use std::sync::Once;
+
+#[cfg(unix)]
+static RAISE_ONCE: Once = Once::new();
+
+/// Raises the soft `RLIMIT_NOFILE` toward the hard limit, once per process. Failures are logged
+/// at debug level and otherwise ignored - a failed raise just means bundling proceeds under
+/// whatever limit the process already had, the same as before this existed.
+#[cfg(unix)]
+pub fn raise_fd_limit() {
+    RAISE_ONCE.call_once(|| {
+        if let Err(err) = try_raise_fd_limit() {
+            log::debug!("Failed to raise file descriptor limit: {err}");
+        }
+    });
+}
+
+#[cfg(unix)]
+fn try_raise_fd_limit() -> std::io::Result<()> {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let ceiling = hard_ceiling(limit.rlim_max);
+    if limit.rlim_cur >= ceiling {
+        return Ok(());
+    }
+
+    limit.rlim_cur = ceiling;
+    if unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// macOS reports `RLIM_INFINITY` as the hard limit but silently refuses any soft limit above
+/// `OPEN_MAX` / the `kern.maxfilesperproc` sysctl - raising past that fails `setrlimit` outright,
+/// so clamp to whichever is smaller before attempting the raise.
+#[cfg(target_os = "macos")]
+fn hard_ceiling(hard_limit: libc::rlim_t) -> libc::rlim_t {
+    let open_max = libc::OPEN_MAX as libc::rlim_t;
+    let max_files_per_proc = macos_max_files_per_proc().unwrap_or(open_max);
+    hard_limit.min(open_max).min(max_files_per_proc)
+}
+
+#[cfg(target_os = "macos")]
+fn macos_max_files_per_proc() -> Option<libc::rlim_t> {
+    let name = std::ffi::CString::new("kern.maxfilesperproc").ok()?;
+    let mut value: libc::c_int = 0;
+    let mut size = std::mem::size_of::<libc::c_int>();
+
+    let result = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            &mut value as *mut _ as *mut libc::c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if result == 0 {
+        Some(value as libc::rlim_t)
+    } else {
+        None
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn hard_ceiling(hard_limit: libc::rlim_t) -> libc::rlim_t {
+    hard_limit
+}
+
+/// No-op on platforms without `RLIMIT_NOFILE` (e.g. Windows).
+#[cfg(not(unix))]
+pub fn raise_fd_limit() {}
@@ -1,14 +1,80 @@
+use std::ops::Range;
+
+/// Characters that, when they're the last significant token seen, mean a following `/` opens a
+/// regular-expression literal rather than being the division operator - see
+/// `strip_js_comments_with_ranges`.
+const REGEX_ALLOWED_AFTER: &[char] = &[
+    '(', ',', '=', ':', '[', '!', '&', '|', '?', '{', '}', ';', '+', '-', '*', '%', '<', '>', '~',
+    '^',
+];
+
 pub fn strip_js_comments(js_string: &str, skip_whitespace: bool) -> String {
+    strip_js_comments_with_ranges(js_string, skip_whitespace).0
+}
+
+/// Same behavior as [`strip_js_comments`], but additionally returns the byte range (into
+/// `js_string`, not the returned string) of every comment span that was removed, so a caller that
+/// strips comments before handing source to the bundler (e.g. SWC) can adjust source-map offsets
+/// instead of having every position after a removed comment silently shift. A line comment's
+/// range ends before its terminating `\n`; that newline is dropped from the output as well but is
+/// not part of any reported range.
+///
+/// Tracks regular-expression literals in addition to strings, so a `/` that's part of one (e.g.
+/// the unescaped `/` pair inside the character class of `/[a//b]/`) is never mistaken for the
+/// start of a line/block comment. Per the ECMAScript grammar, whether a bare `/` opens a regex or
+/// is a division operator is ambiguous without a full parser - this uses the common heuristic of
+/// looking at the last significant token: a regex may start right after an operator, `(`, `,`,
+/// `[`, `{`, `}`, `;`, `return`, or the start of the source, but not after an identifier, `)`,
+/// `]`, or a number literal, since those leave a value behind that `/` would divide.
+pub fn strip_js_comments_with_ranges(
+    js_string: &str,
+    skip_whitespace: bool,
+) -> (String, Vec<Range<usize>>) {
     let mut final_text = String::new();
+    let mut removed_ranges = Vec::new();
+
     let chars: Vec<char> = js_string.chars().collect();
+    let byte_offsets: Vec<usize> = js_string.char_indices().map(|(b, _)| b).collect();
+    let byte_offset_at = |i: usize| -> usize {
+        byte_offsets.get(i).copied().unwrap_or(js_string.len())
+    };
+
     let mut i = 0;
     let mut is_in_block_comment = false;
     let mut is_in_line_comment = false;
+    let mut comment_start: Option<usize> = None;
     // Track the current string delimiter (None if not in a string)
     let mut string_delimiter: Option<char> = None;
+    // Whether a `/` encountered right now would open a regex literal (see `REGEX_ALLOWED_AFTER`).
+    // A source file starts in a position where a regex is legal (e.g. a lone regex statement).
+    let mut regex_allowed = true;
+    let mut is_in_regex = false;
+    let mut is_in_regex_char_class = false;
 
     while i < chars.len() {
         match chars[i] {
+            // Handle regex literals: consume until the matching unescaped `/`, treating `\`
+            // escapes and `[...]` character classes (where `/` isn't a terminator) specially.
+            _ if is_in_regex => {
+                final_text.push(chars[i]);
+                match chars[i] {
+                    '\\' if i + 1 < chars.len() => {
+                        // An escaped character never toggles char-class/terminator state -
+                        // consume it and the escaped char together.
+                        final_text.push(chars[i + 1]);
+                        i += 1;
+                    }
+                    '[' => is_in_regex_char_class = true,
+                    ']' => is_in_regex_char_class = false,
+                    '/' if !is_in_regex_char_class => {
+                        // End of the regex literal. Flags (e.g. the `g` in `/foo/g`) are plain
+                        // identifier characters and fall through to the normal fallback arm below.
+                        is_in_regex = false;
+                        regex_allowed = false;
+                    }
+                    _ => {}
+                }
+            }
             // Handle strings
             '"' | '\'' | '`'
                 if !is_in_block_comment && !is_in_line_comment && string_delimiter.is_none() =>
@@ -20,6 +86,7 @@ pub fn strip_js_comments(js_string: &str, skip_whitespace: bool) -> String {
             ch if Some(ch) == string_delimiter && i > 0 && chars[i - 1] != '\\' => {
                 // Exiting a string
                 string_delimiter = None;
+                regex_allowed = false;
                 final_text.push(chars[i]);
             }
             // Handle comments
@@ -31,23 +98,44 @@ pub fn strip_js_comments(js_string: &str, skip_whitespace: bool) -> String {
                     '/' => {
                         // Double slashes can be nested in a block comment and should be treated
                         // just like a regular string, they will end when the block comment does
-                        // and not when the line does
-                        if !is_in_block_comment {
+                        // and not when the line does. They can also appear *inside* an
+                        // already-open line comment (e.g. a URL like `// see http://x`) - only
+                        // the first `//` on the line actually opens it, so don't re-set
+                        // `comment_start` and lose the real start once one is already open.
+                        if !is_in_block_comment && !is_in_line_comment {
                             is_in_line_comment = true;
+                            comment_start = Some(byte_offset_at(i));
                         }
                         i += 1; // Skip next char as it's part of the comment syntax
                     }
                     '*' => {
+                        if !is_in_block_comment {
+                            comment_start = Some(byte_offset_at(i));
+                        }
                         is_in_block_comment = true;
                         i += 1; // Skip next char as it's part of the comment syntax
                     }
-                    _ => final_text.push(chars[i]),
+                    _ if !is_in_block_comment && !is_in_line_comment && regex_allowed => {
+                        // Not a comment opener, and the last significant token means this `/`
+                        // can only be the start of a regex literal, not division.
+                        is_in_regex = true;
+                        final_text.push(chars[i]);
+                    }
+                    _ => {
+                        if !is_in_block_comment {
+                            regex_allowed = false;
+                            final_text.push(chars[i]);
+                        }
+                    }
                 }
             }
             '*' if is_in_block_comment && i + 1 < chars.len() => {
                 match chars[i + 1] {
                     '/' => {
                         is_in_block_comment = false;
+                        if let Some(start) = comment_start.take() {
+                            removed_ranges.push(start..byte_offset_at(i + 2));
+                        }
                         i += 1; // Skip next char as it's part of the comment syntax
                     }
                     _ => final_text.push(chars[i]),
@@ -55,9 +143,38 @@ pub fn strip_js_comments(js_string: &str, skip_whitespace: bool) -> String {
             }
             '\n' if is_in_line_comment => {
                 is_in_line_comment = false;
+                if let Some(start) = comment_start.take() {
+                    removed_ranges.push(start..byte_offset_at(i));
+                }
             }
             // Skip over all whitespaces outside of strings
             ch if ch.is_whitespace() && skip_whitespace && string_delimiter.is_none() => (),
+            // Identifiers/keywords and number literals leave a value behind, so a following `/`
+            // divides rather than opening a regex - except `return`, which is a keyword, not a
+            // value, and is followed by an operand just like the start of a statement.
+            ch if (ch.is_alphanumeric() || ch == '_' || ch == '$')
+                && !is_in_block_comment
+                && !is_in_line_comment =>
+            {
+                let word_start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                {
+                    final_text.push(chars[i]);
+                    i += 1;
+                }
+                let word: String = chars[word_start..i].iter().collect();
+                regex_allowed = word == "return";
+                i -= 1; // The outer loop's `i += 1` advances past the word's last character.
+            }
+            ')' | ']' if !is_in_block_comment && !is_in_line_comment => {
+                regex_allowed = false;
+                final_text.push(chars[i]);
+            }
+            ch if REGEX_ALLOWED_AFTER.contains(&ch) && !is_in_block_comment && !is_in_line_comment => {
+                regex_allowed = true;
+                final_text.push(chars[i]);
+            }
             // Fallback for normal non-comment characters
             _ if !is_in_block_comment && !is_in_line_comment => {
                 final_text.push(chars[i]);
@@ -68,7 +185,12 @@ pub fn strip_js_comments(js_string: &str, skip_whitespace: bool) -> String {
         i += 1;
     }
 
-    final_text
+    if let Some(start) = comment_start {
+        // An unterminated line comment runs to the end of the source.
+        removed_ranges.push(start..js_string.len());
+    }
+
+    (final_text, removed_ranges)
 }
 
 #[cfg(test)]
@@ -126,4 +248,52 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_strip_js_comments_does_not_truncate_regex_with_unescaped_slashes_in_char_class() {
+        let input = "const re = /[a//b]/; const y = 5; // trailing comment";
+        assert_eq!(
+            strip_js_comments(input, false),
+            "const re = /[a//b]/; const y = 5; "
+        );
+    }
+
+    #[test]
+    fn test_strip_js_comments_preserves_escaped_slashes_in_regex() {
+        let input = r#"const re = /foo\/\/bar/; const y = 5;"#;
+        assert_eq!(strip_js_comments(input, false), input);
+    }
+
+    #[test]
+    fn test_strip_js_comments_treats_division_after_identifier_as_division() {
+        // `a` is an identifier, so the `/` right after it divides rather than opening a regex -
+        // the line comment that follows should still be stripped.
+        let input = "let x = a / b; // comment";
+        assert_eq!(strip_js_comments(input, false), "let x = a / b; ");
+    }
+
+    #[test]
+    fn test_strip_js_comments_with_ranges_reports_removed_comment_spans() {
+        let input = "let x = 5; // line comment\nlet y = /* block */ 10;";
+        let (stripped, ranges) = strip_js_comments_with_ranges(input, false);
+
+        assert_eq!(stripped, "let x = 5; let y =  10;");
+
+        let removed: Vec<&str> = ranges.iter().map(|r| &input[r.clone()]).collect();
+        assert_eq!(removed, vec!["// line comment", "/* block */"]);
+    }
+
+    #[test]
+    fn test_strip_js_comments_with_ranges_handles_embedded_double_slash() {
+        // A `//` inside an already-open line comment (e.g. a URL) must not reset
+        // `comment_start` - the reported range has to cover the whole comment from its real
+        // start, not just from the last `//` onward, or a caller adjusting source-map offsets
+        // from `removed_ranges` will treat deleted bytes as still-present source.
+        let input = "// See http://x\ny";
+        let (stripped, ranges) = strip_js_comments_with_ranges(input, false);
+
+        assert_eq!(stripped, "y");
+        assert_eq!(ranges, vec![0..15]);
+        assert_eq!(&input[ranges[0].clone()], "// See http://x");
+    }
 }
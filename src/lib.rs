@@ -2,28 +2,46 @@ use errors::AppError;
 use log::debug;
 use pyo3::exceptions::{PyConnectionAbortedError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyString};
+use pyo3::types::{PyDict, PyList, PyString};
+use std::path::PathBuf;
 
+mod bundle;
+mod bundle_cache;
 mod bundle_common;
 mod bundle_independent;
 mod bundle_prod;
+mod bundle_watch;
 mod code_gen;
+mod cpu_watchdog;
+mod dependencies;
 mod errors;
+mod fd_limit;
+mod import_map;
+mod inspector;
 mod lexers;
 mod logging;
+mod module_loader;
+mod ops;
+mod resource_limits;
+mod snapshot;
 mod source_map;
 mod ssr;
+mod thread_cpu;
 mod timeout;
 
 #[macro_use]
 extern crate lazy_static;
 
 // Export mainly for use in benchmarks
-pub use lexers::strip_js_comments;
+pub use lexers::{strip_js_comments, strip_js_comments_with_ranges};
 pub use source_map::{
     make_source_map_paths_absolute, update_source_map_path, MapMetadata, SourceMapParser,
     VLQDecoder,
 };
+pub use bundle_cache::bundle_common_cached;
+pub use bundle_watch::bundle_watch;
+pub use module_loader::{FsModuleLoader, InMemoryModuleLoader, ModuleLoader};
+pub use snapshot::{build_snapshot, cached_snapshot};
 pub use ssr::Ssr;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -87,7 +105,12 @@ fn mountaineer(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
          *
          * :raises ConnectionAbortedError: if the hard_timeout is reached
          * :raises ValueError: if the V8 engine throws an exception, since there's probably
-         *   something wrong with the script
+         *   something wrong with the script. Carries `class_name` (the thrown value's
+         *   constructor name, e.g. "TypeError", or None) and `frames` (a list of
+         *   `{function_name, file_name, line, column}` dicts) attributes in addition to the
+         *   flattened message in `str(err)`. Also carries `raw_stack`, the unremapped V8 trace
+         *   (still pointing into bundled output, not original source) - only populated when the
+         *   `MOUNTAINEER_SSR_RAW_STACK` env var is set, for debugging source map remapping itself.
          */
         if cfg!(debug_assertions) {
             debug!("Running in debug mode");
@@ -102,7 +125,33 @@ fn mountaineer(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
             }
             Err(err) => match err {
                 AppError::HardTimeoutError(msg) => Err(PyConnectionAbortedError::new_err(msg)),
-                AppError::V8ExceptionError(msg) => Err(PyValueError::new_err(msg)),
+                AppError::SoftTimeoutError(msg) => Err(PyConnectionAbortedError::new_err(msg)),
+                AppError::V8ExceptionError(js_error) => {
+                    // Raise a plain ValueError (same as before) but attach the structured fields
+                    // `JsError` captured, so Python callers can classify the failure or render a
+                    // richer error page instead of parsing `str(err)` by hand.
+                    let py_err = PyValueError::new_err(js_error.message.clone());
+
+                    let frames = PyList::empty(py);
+                    for frame in &js_error.frames {
+                        let frame_dict = PyDict::new(py);
+                        frame_dict.set_item("function_name", frame.function_name.clone())?;
+                        frame_dict.set_item("file_name", frame.file_name.clone())?;
+                        frame_dict.set_item("line", frame.line)?;
+                        frame_dict.set_item("column", frame.column)?;
+                        frames.append(frame_dict)?;
+                    }
+
+                    py_err
+                        .value(py)
+                        .setattr("class_name", js_error.class_name.clone())?;
+                    py_err.value(py).setattr("frames", frames)?;
+                    py_err
+                        .value(py)
+                        .setattr("raw_stack", js_error.raw_stack.clone())?;
+
+                    Err(py_err)
+                }
             },
         }
     }
@@ -130,7 +179,7 @@ fn mountaineer(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     #[pyfn(m)]
     #[pyo3(name = "compile_independent_bundles")]
-    #[pyo3(signature = (paths, node_modules_path, environment, live_reload_port, live_reload_import, is_server, tsconfig_path=None))]
+    #[pyo3(signature = (paths, node_modules_path, environment, live_reload_port, live_reload_import, is_server, tsconfig_path=None, max_concurrency=None, cache_dir=None))]
     #[allow(clippy::too_many_arguments)]
     fn compile_independent_bundles(
         py: Python,
@@ -141,6 +190,8 @@ fn mountaineer(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
         live_reload_import: String,
         is_server: bool,
         tsconfig_path: Option<String>,
+        max_concurrency: Option<usize>,
+        cache_dir: Option<String>,
     ) -> PyResult<(Vec<String>, Vec<String>)> {
         /*
          * Accepts a list of page definitions and creates fully isolated bundles
@@ -164,12 +215,87 @@ fn mountaineer(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
             live_reload_import,
             is_server,
             tsconfig_path,
+            max_concurrency,
+            cache_dir,
         )
     }
 
+    #[pyfn(m)]
+    #[pyo3(name = "watch_dependency_graph")]
+    fn watch_dependency_graph(
+        py: Python,
+        root_dir: String,
+        roots: Vec<String>,
+        callback: Py<PyAny>,
+    ) -> PyResult<()> {
+        /*
+         * :param root_dir: the project root to build and watch the dependency graph for
+         * :param roots: the entrypoint paths whose affected-ness is reported back to `callback`
+         * :param callback: called with a `list[str]` of affected root paths whenever a filesystem
+         *   change ripples up to one of `roots`. Runs on this same thread, so it should return
+         *   quickly.
+         *
+         * Blocks for as long as the watch stays alive (i.e. until the underlying watcher hits a
+         * fatal error) - call this from a background thread on the Python side.
+         */
+        let watcher = dependencies::DependencyWatcher::new(PathBuf::from(root_dir), None)
+            .map_err(PyValueError::new_err)?;
+        let root_entrypoints: Vec<PathBuf> = roots.into_iter().map(PathBuf::from).collect();
+
+        py.allow_threads(|| {
+            watcher.watch_and_notify(root_entrypoints, |affected_roots| {
+                Python::with_gil(|py| {
+                    let paths: Vec<String> = affected_roots
+                        .into_iter()
+                        .map(|p| p.to_string_lossy().into_owned())
+                        .collect();
+                    if let Err(e) = callback.call1(py, (paths,)) {
+                        e.print(py);
+                    }
+                });
+            })
+        })
+        .map_err(PyValueError::new_err)
+    }
+
+    #[pyfn(m)]
+    #[pyo3(name = "topological_build_order")]
+    fn topological_build_order(py: Python, root_dir: String) -> PyResult<Py<PyAny>> {
+        /*
+         * :param root_dir: the project root to build the dependency graph for
+         * :returns: a `list[str]` build order (dependencies before dependents) if the graph has
+         *   no import cycles, otherwise a `list[list[str]]` - one inner list per circular import
+         *   chain - so the caller can report exactly which modules are involved.
+         */
+        let watcher = dependencies::DependencyWatcher::new(PathBuf::from(root_dir), None)
+            .map_err(PyValueError::new_err)?;
+
+        match watcher.topological_order() {
+            dependencies::TopoResult::Ordered(paths) => {
+                let paths: Vec<String> = paths
+                    .into_iter()
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .collect();
+                Ok(paths.into_pyobject(py)?.into_any().unbind())
+            }
+            dependencies::TopoResult::Cycles(cycles) => {
+                let cycles: Vec<Vec<String>> = cycles
+                    .into_iter()
+                    .map(|cycle| {
+                        cycle
+                            .into_iter()
+                            .map(|p| p.to_string_lossy().into_owned())
+                            .collect()
+                    })
+                    .collect();
+                Ok(cycles.into_pyobject(py)?.into_any().unbind())
+            }
+        }
+    }
+
     #[pyfn(m)]
     #[pyo3(name = "compile_production_bundle")]
-    #[pyo3(signature = (paths, node_modules_path, environment, minify, live_reload_import, is_server, tsconfig_path=None))]
+    #[pyo3(signature = (paths, node_modules_path, environment, minify, live_reload_import, is_server, tsconfig_path=None, content_hash=false, import_map=None, profile=false, define=None))]
     #[allow(clippy::too_many_arguments)]
     fn compile_production_bundle(
         py: Python,
@@ -180,6 +306,10 @@ fn mountaineer(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
         live_reload_import: String,
         is_server: bool,
         tsconfig_path: Option<String>,
+        content_hash: bool,
+        import_map: Option<String>,
+        profile: bool,
+        define: Option<std::collections::HashMap<String, String>>,
     ) -> PyResult<Py<PyDict>> {
         /*
          * Builds a full production bundle from multiple JavaScript files. Uses
@@ -199,6 +329,10 @@ fn mountaineer(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
             live_reload_import,
             is_server,
             tsconfig_path,
+            content_hash,
+            import_map,
+            profile,
+            define,
         )
     }
 
@@ -0,0 +1,168 @@
+use crate::errors::AppError;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Resolves and loads ES module source text by specifier, so `Ssr`'s module-graph renderer (see
+/// `Ssr::render_module`) isn't hard-coded to a single resolution strategy. Modeled on
+/// deno_core's `ModuleLoader` trait, trimmed to the synchronous, static-`import`-only subset that
+/// module-graph rendering needs.
+pub trait ModuleLoader: Send + Sync {
+    /// Resolves `specifier`, as written in an `import`/`export ... from` statement inside
+    /// `referrer`, to the canonical specifier `load` accepts. `referrer` is the entry module's own
+    /// specifier for its top-level imports, or whichever module did the importing otherwise.
+    fn resolve(&self, specifier: &str, referrer: &str) -> Result<String, AppError>;
+
+    /// Returns the source text for an already-resolved `specifier`.
+    fn load(&self, specifier: &str) -> Result<String, AppError>;
+}
+
+/// Loads modules straight off disk, resolving relative specifiers against `referrer`'s directory
+/// the way Node/ESM does. Bare specifiers (package imports) aren't supported - pair with a bundler
+/// step (or `InMemoryModuleLoader`) for those instead.
+pub struct FsModuleLoader;
+
+impl ModuleLoader for FsModuleLoader {
+    fn resolve(&self, specifier: &str, referrer: &str) -> Result<String, AppError> {
+        let referrer_dir = Path::new(referrer)
+            .parent()
+            .unwrap_or_else(|| Path::new("."));
+        // Collapse `.`/`..` segments so the same module always resolves to one specifier.
+        let mut resolved = referrer_dir.to_path_buf();
+        for component in Path::new(specifier).components() {
+            match component {
+                std::path::Component::CurDir => {}
+                std::path::Component::ParentDir => {
+                    resolved.pop();
+                }
+                other => resolved.push(other.as_os_str()),
+            }
+        }
+        Ok(resolved.to_string_lossy().into_owned())
+    }
+
+    fn load(&self, specifier: &str) -> Result<String, AppError> {
+        std::fs::read_to_string(specifier).map_err(|err| {
+            AppError::V8ExceptionError(
+                format!("Failed to read module '{}': {}", specifier, err).into(),
+            )
+        })
+    }
+}
+
+/// Loads modules from an in-memory specifier -> source map instead of the filesystem - useful for
+/// tests, or for feeding already-transpiled TSX straight in without writing it to disk first.
+#[derive(Default)]
+pub struct InMemoryModuleLoader {
+    modules: HashMap<String, String>,
+}
+
+impl InMemoryModuleLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, specifier: &str, source: &str) {
+        self.modules
+            .insert(specifier.to_string(), source.to_string());
+    }
+}
+
+impl ModuleLoader for InMemoryModuleLoader {
+    fn resolve(&self, specifier: &str, _referrer: &str) -> Result<String, AppError> {
+        // A leading `./` is dropped, so `./helper.js` finds a module registered as `helper.js`.
+        Ok(specifier.trim_start_matches("./").to_string())
+    }
+
+    fn load(&self, specifier: &str) -> Result<String, AppError> {
+        self.modules.get(specifier).cloned().ok_or_else(|| {
+            AppError::V8ExceptionError(
+                format!("No module registered under specifier '{}'", specifier).into(),
+            )
+        })
+    }
+}
+
+/// Pulls every `import`/`export ... from` specifier out of `source` - just enough parsing to
+/// discover a static module graph's edges, not a full parser. Dynamic `import()` isn't supported
+/// since it would need async module instantiation; every `import`/`export from` this matches is
+/// assumed to name a module that must be loaded before `source` can be instantiated.
+pub fn extract_import_specifiers(source: &str) -> Vec<String> {
+    lazy_static! {
+        static ref IMPORT_RE: regex::Regex =
+            regex::Regex::new(r#"(?:import|export)\s[^;]*?\sfrom\s*['"]([^'"]+)['"]"#).unwrap();
+        static ref BARE_IMPORT_RE: regex::Regex =
+            regex::Regex::new(r#"import\s*['"]([^'"]+)['"]"#).unwrap();
+    }
+
+    let mut specifiers: Vec<String> = IMPORT_RE
+        .captures_iter(source)
+        .map(|caps| caps[1].to_string())
+        .collect();
+    specifiers.extend(
+        BARE_IMPORT_RE
+            .captures_iter(source)
+            .map(|caps| caps[1].to_string()),
+    );
+    specifiers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_import_specifiers_finds_named_and_default_imports() {
+        let source = r#"
+            import React from 'react';
+            import { useState } from "react";
+            export { helper } from './helper.js';
+            export default function () {}
+        "#;
+
+        let mut specifiers = extract_import_specifiers(source);
+        specifiers.sort();
+
+        assert_eq!(specifiers, vec!["./helper.js", "react", "react"]);
+    }
+
+    #[test]
+    fn test_extract_import_specifiers_finds_bare_side_effect_imports() {
+        let source = r#"import './polyfills.js';"#;
+        assert_eq!(
+            extract_import_specifiers(source),
+            vec!["./polyfills.js".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_fs_module_loader_resolves_relative_to_referrer_directory() {
+        let loader = FsModuleLoader;
+        let resolved = loader
+            .resolve("./helper.js", "/project/src/entry.js")
+            .unwrap();
+
+        assert_eq!(resolved, "/project/src/helper.js");
+    }
+
+    #[test]
+    fn test_in_memory_module_loader_round_trips_registered_source() {
+        let mut loader = InMemoryModuleLoader::new();
+        loader.add("entry.js", "export default () => 'hi';");
+
+        let resolved = loader.resolve("entry.js", "").unwrap();
+        assert_eq!(loader.load(&resolved).unwrap(), "export default () => 'hi';");
+    }
+
+    #[test]
+    fn test_in_memory_module_loader_errors_on_unknown_specifier() {
+        let loader = InMemoryModuleLoader::new();
+        let result = loader.load("missing.js");
+
+        assert_eq!(
+            result,
+            Err(AppError::V8ExceptionError(
+                "No module registered under specifier 'missing.js'".into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::errors::AppError;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A Rust closure JS can call back into during SSR. Arguments are already-decoded
+/// `serde_json::Value`s (see `Ssr::install_ops`'s V8 <-> JSON bridge) and the return value is
+/// serialized back into a V8 value for the caller.
+pub type Op = Box<dyn Fn(&[Value]) -> Result<Value, AppError> + Send + Sync>;
+
+/// A registry of named ops, generalizing the one-off `console.*` bridge `Ssr::inject_logger`
+/// hard-codes into a reusable mechanism - modeled on deno_core's ops. `Ssr::install_ops` installs
+/// every registered op as `globalThis.__ops.<name>` before the entry point runs, so render code
+/// can fetch data, read config, or call host services synchronously instead of requiring
+/// everything be pre-serialized into `render_to_string`'s single `params` string.
+#[derive(Default)]
+pub struct OpRegistry {
+    ops: HashMap<String, Op>,
+}
+
+impl OpRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `op` under `name` - calling `globalThis.__ops.<name>(...)` from JS dispatches to
+    /// it, with arguments decoded to `serde_json::Value` and the result encoded back the same way.
+    pub fn register<F>(&mut self, name: &str, op: F)
+    where
+        F: Fn(&[Value]) -> Result<Value, AppError> + Send + Sync + 'static,
+    {
+        self.ops.insert(name.to_string(), Box::new(op));
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.ops.keys()
+    }
+
+    pub fn call(&self, name: &str, args: &[Value]) -> Result<Value, AppError> {
+        match self.ops.get(name) {
+            Some(op) => op(args),
+            None => Err(AppError::V8ExceptionError(
+                format!("No op registered under the name '{}'", name).into(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_register_and_call_dispatches_to_the_closure() {
+        let mut registry = OpRegistry::new();
+        registry.register("double", |args| {
+            let n = args[0].as_i64().unwrap_or(0);
+            Ok(json!(n * 2))
+        });
+
+        let result = registry.call("double", &[json!(21)]).unwrap();
+        assert_eq!(result, json!(42));
+    }
+
+    #[test]
+    fn test_call_unknown_op_returns_an_error() {
+        let registry = OpRegistry::new();
+        let result = registry.call("missing", &[]);
+
+        assert_eq!(
+            result,
+            Err(AppError::V8ExceptionError(
+                "No op registered under the name 'missing'".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_call_propagates_the_ops_own_error() {
+        let mut registry = OpRegistry::new();
+        registry.register("always_fails", |_args| {
+            Err(AppError::V8ExceptionError("op failed".into()))
+        });
+
+        let result = registry.call("always_fails", &[]);
+
+        assert_eq!(
+            result,
+            Err(AppError::V8ExceptionError("op failed".into()))
+        );
+    }
+}
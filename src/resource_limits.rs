@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Resource ceilings as seen from inside whatever cgroup this process is confined to, falling
+/// back to the host's own core count (and no memory ceiling) when no cgroup limit applies - e.g.
+/// running outside a container, on a platform without cgroups, or without permission to read
+/// `/sys/fs/cgroup`.
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceLimits {
+    /// CPU cores this process may actually use, after clamping any cgroup CPU quota to the
+    /// host's physical core count. Used to size the bundler's Tokio runtime so it doesn't
+    /// oversubscribe a container that reports a misleading host-wide core count.
+    pub effective_cpus: usize,
+    /// cgroup memory ceiling in bytes, if one is set.
+    pub memory_limit_bytes: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Detects the effective CPU/memory ceiling for the current process, preferring cgroup v2
+    /// (`cpu.max` / `memory.max`) and falling back to cgroup v1 (`cpu.cfs_quota_us` +
+    /// `cpu.cfs_period_us` / `memory.limit_in_bytes`).
+    pub fn detect() -> Self {
+        let physical_cores = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+
+        let Some(cgroup_root) = resolve_cgroup_root() else {
+            return Self {
+                effective_cpus: physical_cores,
+                memory_limit_bytes: None,
+            };
+        };
+
+        let cpu_limit =
+            detect_cpu_limit_v2(&cgroup_root).or_else(|| detect_cpu_limit_v1(&cgroup_root));
+        let memory_limit_bytes = detect_memory_limit_v2(&cgroup_root)
+            .or_else(|| detect_memory_limit_v1(&cgroup_root));
+
+        let effective_cpus = cpu_limit
+            .map(|limit| limit.min(physical_cores))
+            .unwrap_or(physical_cores)
+            .max(1);
+
+        Self {
+            effective_cpus,
+            memory_limit_bytes,
+        }
+    }
+}
+
+/// Resolves this process's own cgroup directory by parsing `/proc/self/cgroup`, rather than
+/// assuming the host's cgroup root - inside a container or nested sandbox, the process's cgroup
+/// is almost never `/sys/fs/cgroup` itself.
+fn resolve_cgroup_root() -> Option<PathBuf> {
+    let contents = fs::read_to_string("/proc/self/cgroup").ok()?;
+
+    // cgroup v2 reports a single unified line ("0::/path"). cgroup v1 reports one line per
+    // controller ("4:cpu,cpuacct:/path") - prefer the entry that lists the `cpu` controller
+    // (or the unified empty-controller line) since that's what carries the quota we care about.
+    let relative = contents
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.splitn(3, ':');
+            let _hierarchy_id = parts.next()?;
+            let controllers = parts.next()?;
+            let path = parts.next()?;
+            if controllers.is_empty() || controllers.split(',').any(|c| c == "cpu") {
+                Some(path)
+            } else {
+                None
+            }
+        })
+        .or_else(|| contents.lines().next().and_then(|line| line.splitn(3, ':').nth(2)))?;
+
+    let trimmed = relative.trim_start_matches('/');
+    if trimmed.is_empty() {
+        Some(PathBuf::from("/sys/fs/cgroup"))
+    } else {
+        Some(PathBuf::from("/sys/fs/cgroup").join(trimmed))
+    }
+}
+
+fn detect_cpu_limit_v2(cgroup_root: &Path) -> Option<usize> {
+    let contents = fs::read_to_string(cgroup_root.join("cpu.max")).ok()?;
+    let mut parts = contents.split_whitespace();
+    let max = parts.next()?;
+    let period: f64 = parts.next()?.parse().ok()?;
+    if max == "max" {
+        return None;
+    }
+    let quota: f64 = max.parse().ok()?;
+    Some((quota / period).ceil() as usize)
+}
+
+fn detect_cpu_limit_v1(cgroup_root: &Path) -> Option<usize> {
+    let quota: f64 = fs::read_to_string(cgroup_root.join("cpu.cfs_quota_us"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    // A quota of -1 (or, defensively, any non-positive value) means "unlimited" under cgroup v1.
+    if quota <= 0.0 {
+        return None;
+    }
+    let period: f64 = fs::read_to_string(cgroup_root.join("cpu.cfs_period_us"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some((quota / period).ceil() as usize)
+}
+
+fn detect_memory_limit_v2(cgroup_root: &Path) -> Option<u64> {
+    let contents = fs::read_to_string(cgroup_root.join("memory.max")).ok()?;
+    let trimmed = contents.trim();
+    if trimmed == "max" {
+        return None;
+    }
+    trimmed.parse().ok()
+}
+
+fn detect_memory_limit_v1(cgroup_root: &Path) -> Option<u64> {
+    let contents = fs::read_to_string(cgroup_root.join("memory.limit_in_bytes")).ok()?;
+    let limit: u64 = contents.trim().parse().ok()?;
+    // cgroup v1 represents "unlimited" as a huge sentinel value (close to i64::MAX rounded down
+    // to a page boundary) rather than omitting the file, so treat anything absurdly large as
+    // unset.
+    if limit > (1u64 << 62) {
+        None
+    } else {
+        Some(limit)
+    }
+}
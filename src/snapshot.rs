@@ -0,0 +1,114 @@
+use crate::ssr::Ssr;
+use rustc_hash::FxHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// Runs `source` inside a `v8::SnapshotCreator` and captures the resulting heap as a blob.
+///
+/// `source` should be the "static" prefix shared by every render - polyfills plus the framework
+/// runtime - rather than page-specific code: isolates built from the returned blob (via
+/// `v8::CreateParams::default().snapshot_blob(...)`) start with `source` already compiled and
+/// executed, so per-request rendering only has to handle the small page-specific entry point.
+/// Modeled on deno_core's `snapshot_util` -
+/// https://github.com/denoland/deno_core/blob/main/core/snapshot_util.rs.
+pub fn build_snapshot(source: &str) -> Vec<u8> {
+    Ssr::init_platform();
+
+    let mut isolate = v8::Isolate::snapshot_creator(None);
+
+    {
+        let handle_scope = &mut v8::HandleScope::new(&mut isolate);
+        let context = v8::Context::new(handle_scope);
+        let scope = &mut v8::ContextScope::new(handle_scope, context);
+
+        let code =
+            v8::String::new(scope, source).expect("Failed to create snapshot source string");
+        let script =
+            v8::Script::compile(scope, code, None).expect("Failed to compile snapshot source");
+        script.run(scope).expect("Failed to run snapshot source");
+
+        scope.set_default_context(context);
+    }
+
+    isolate
+        .create_blob(v8::FunctionCodeHandling::Keep)
+        .expect("Failed to create snapshot blob")
+        .to_vec()
+}
+
+/// Hashes `source` into the filename a cached snapshot for it is stored under, so editing the
+/// static prefix invalidates the cache automatically instead of silently serving a stale blob.
+fn cache_key(source: &str) -> String {
+    let mut hasher = FxHasher::default();
+    source.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Returns a snapshot blob for `source`, reusing the copy cached in `cache_dir` when present and
+/// building (then writing) a fresh one otherwise.
+pub fn cached_snapshot(source: &str, cache_dir: &Path) -> std::io::Result<Vec<u8>> {
+    fs::create_dir_all(cache_dir)?;
+    let cache_path = cache_dir.join(format!("{}.v8snapshot", cache_key(source)));
+
+    if let Ok(cached) = fs::read(&cache_path) {
+        return Ok(cached);
+    }
+
+    let blob = build_snapshot(source);
+
+    // Written via a sibling temp file plus rename, rather than a plain `fs::write`, so a reader
+    // racing a concurrent writer for this same content-hashed key never observes a
+    // partially-written `.v8snapshot` file - same idiom as `bundle_independent`'s `atomic_write`.
+    let mut temp_file = NamedTempFile::new_in(cache_dir)?;
+    temp_file.write_all(&blob)?;
+    temp_file
+        .persist(&cache_path)
+        .map_err(|e| e.error)?;
+
+    Ok(blob)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cache_key_is_stable_and_content_sensitive() {
+        assert_eq!(cache_key("var x = 1;"), cache_key("var x = 1;"));
+        assert_ne!(cache_key("var x = 1;"), cache_key("var x = 2;"));
+    }
+
+    #[test]
+    fn test_cached_snapshot_reuses_disk_copy() {
+        let temp_dir = tempdir().unwrap();
+
+        let first = cached_snapshot("var x = 1;", temp_dir.path()).unwrap();
+        let cache_path = temp_dir
+            .path()
+            .join(format!("{}.v8snapshot", cache_key("var x = 1;")));
+        assert!(cache_path.exists());
+
+        let second = cached_snapshot("var x = 1;", temp_dir.path()).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_cached_snapshot_leaves_no_temp_files_behind() {
+        let temp_dir = tempdir().unwrap();
+
+        cached_snapshot("var x = 1;", temp_dir.path()).unwrap();
+
+        let remaining: Vec<_> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![format!("{}.v8snapshot", cache_key("var x = 1;"))]
+        );
+    }
+}
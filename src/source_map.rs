@@ -40,11 +40,18 @@ impl MapMetadata {
 
 pub struct SourceMapParser {
     vlq_decoder: VLQDecoder,
+    // Every parsed segment, grouped by its (0-indexed) generated line and kept sorted by
+    // `column_number`, so `resolve` can binary-search for the segment covering a given column
+    // instead of requiring an exact-key match. Rebuilt from scratch on every `parse_mapping` call.
+    lines: Vec<Vec<MapMetadata>>,
 }
 
 impl SourceMapParser {
     pub fn new(vlq_decoder: VLQDecoder) -> Self {
-        Self { vlq_decoder }
+        Self {
+            vlq_decoder,
+            lines: Vec::new(),
+        }
     }
 
     pub fn parse_mapping(
@@ -55,9 +62,12 @@ impl SourceMapParser {
             std::collections::HashMap::new();
 
         let mut metadata_state = MapMetadata::new(-1, -1);
+        self.lines.clear();
 
         // Empty lines will have semi-colons next to one another
         for (line, encoded_metadata) in mappings.split(';').enumerate() {
+            self.lines.push(Vec::new());
+
             for component in encoded_metadata.split(',') {
                 if component.trim().is_empty() {
                     continue;
@@ -66,6 +76,8 @@ impl SourceMapParser {
                 let mut metadata = self.vlq_to_source_metadata(line as i32, component)?;
                 metadata = self.merge_relative_metadatas(metadata, &mut metadata_state);
 
+                self.lines[line].push(metadata.clone());
+
                 parsed_mappings.insert(
                     // 1-index line numbers to match Javascript exception formatting
                     (metadata.line_number + 1, metadata.column_number + 1),
@@ -74,9 +86,134 @@ impl SourceMapParser {
             }
         }
 
+        for line_segments in &mut self.lines {
+            line_segments.sort_by_key(|segment| segment.column_number);
+        }
+
         Ok(parsed_mappings)
     }
 
+    /// Parses a full source map JSON document - flat or indexed (sectioned) - into the same
+    /// combined `(line, column) -> MapMetadata` lookup table [`Self::parse_mapping`] produces for
+    /// a flat map's bare `mappings` string.
+    ///
+    /// Note that [`Self::resolve`]'s per-line index is rebuilt on every inner `parse_mapping`
+    /// call, so after parsing an indexed map it only reflects the last section processed - use the
+    /// returned lookup table directly for indexed maps instead of `resolve`.
+    pub fn parse_source_map(
+        &mut self,
+        contents: &str,
+    ) -> Result<std::collections::HashMap<(i32, i32), MapMetadata>, String> {
+        let schema: SourceMapSchema = serde_json::from_str(contents)
+            .map_err(|e| format!("Failed to parse source map JSON: {:?}", e))?;
+
+        self.parse_schema(&schema)
+    }
+
+    /// Parses an already-deserialized [`SourceMapSchema`], flattening `sections` (recursively, in
+    /// case a section's own inline map is itself indexed) when present. Each section's entries are
+    /// shifted by its `offset` before being merged into the combined table: `offset.line` applies
+    /// to every segment, while `offset.column` only applies to segments on the section's own first
+    /// (0-indexed) generated line, per the index source map spec.
+    fn parse_schema(
+        &mut self,
+        schema: &SourceMapSchema,
+    ) -> Result<std::collections::HashMap<(i32, i32), MapMetadata>, String> {
+        let Some(sections) = &schema.sections else {
+            return self.parse_mapping(&schema.mappings);
+        };
+
+        let mut combined = std::collections::HashMap::new();
+
+        for section in sections {
+            let Some(inner_schema) = &section.map else {
+                // A `url`-referenced section points at an external map file; fetching it is out
+                // of scope here, so it's skipped rather than failing the whole map.
+                continue;
+            };
+
+            for ((line, column), mut metadata) in self.parse_schema(inner_schema)? {
+                let is_first_line = metadata.line_number == 0;
+                let shifted_line = line + section.offset.line;
+                let shifted_column = if is_first_line {
+                    column + section.offset.column
+                } else {
+                    column
+                };
+
+                metadata.line_number = shifted_line - 1;
+                metadata.column_number = shifted_column - 1;
+
+                combined.insert((shifted_line, shifted_column), metadata);
+            }
+        }
+
+        Ok(combined)
+    }
+
+    /// Resolves a 1-indexed `(line, column)` generated position (matching the keys returned by
+    /// [`Self::parse_mapping`] and the way JS exceptions report stack-trace positions) to the
+    /// segment that covers it: the segment on that line whose column is the greatest one `<=
+    /// column`. If the requested line has no segment starting at or before `column` (including
+    /// because the line itself has no segments at all), falls back to the last segment of the
+    /// nearest preceding line. Returns `None` if there's no candidate at all, e.g. `line` is
+    /// before the first mapped line, and also whenever `line` is past the last line the mappings
+    /// cover (no fallback is tried then).
+    pub fn resolve(&self, line: i32, column: i32) -> Option<MapMetadata> {
+        let target_line = line - 1;
+        let target_column = column - 1;
+
+        if target_line < 0 || target_line as usize >= self.lines.len() {
+            return None;
+        }
+
+        if let Some(found) =
+            Self::last_segment_at_or_before(&self.lines[target_line as usize], target_column)
+        {
+            return Some(found.clone());
+        }
+
+        for preceding_line in (0..target_line).rev() {
+            if let Some(last) = self.lines[preceding_line as usize].last() {
+                return Some(last.clone());
+            }
+        }
+
+        None
+    }
+
+    /// Rebuilds `self.lines` (the per-line index backing [`Self::resolve`]) from an arbitrary,
+    /// possibly non-contiguous set of absolute segments. Used by [`SourceMap::parse`] to make
+    /// `resolve` correct over a flattened indexed (sectioned) map, where the per-call rebuilding
+    /// inside [`Self::parse_schema`]'s recursion would otherwise only reflect the last section.
+    fn rebuild_lines_from(&mut self, metadatas: impl Iterator<Item = MapMetadata>) {
+        self.lines.clear();
+
+        for metadata in metadatas {
+            let line_idx = metadata.line_number.max(0) as usize;
+            if self.lines.len() <= line_idx {
+                self.lines.resize(line_idx + 1, Vec::new());
+            }
+            self.lines[line_idx].push(metadata);
+        }
+
+        for line_segments in &mut self.lines {
+            line_segments.sort_by_key(|segment| segment.column_number);
+        }
+    }
+
+    /// Within a line's segments (sorted ascending by `column_number`), finds the greatest one
+    /// `<= column` via `partition_point`'s binary search for the first segment that fails the
+    /// predicate - the match we want sits just before it.
+    fn last_segment_at_or_before(segments: &[MapMetadata], column: i32) -> Option<&MapMetadata> {
+        let idx = segments.partition_point(|segment| segment.column_number <= column);
+        if idx == 0 {
+            None
+        } else {
+            Some(&segments[idx - 1])
+        }
+    }
+
     fn merge_relative_metadatas(
         &self,
         mut current_metadata: MapMetadata,
@@ -179,6 +316,125 @@ impl SourceMapParser {
     }
 }
 
+/// Inverts [`VLQDecoder`]: encodes signed integers into the same base64-VLQ alphabet instead of
+/// decoding out of it.
+pub struct VLQEncoder {
+    alphabet: Vec<char>,
+}
+
+impl VLQEncoder {
+    pub fn new() -> Self {
+        Self {
+            alphabet: Self::generate_base64_alphabet(),
+        }
+    }
+
+    fn generate_base64_alphabet() -> Vec<char> {
+        let mut alphabet = Vec::new();
+        let alpha_ranges = vec![('A', 'Z'), ('a', 'z'), ('0', '9')];
+
+        for (start, end) in alpha_ranges {
+            for c in start..=end {
+                alphabet.push(c);
+            }
+        }
+
+        alphabet.push('+');
+        alphabet.push('/');
+
+        alphabet
+    }
+
+    /// Encodes a single signed integer: the sign is folded into bit 0 of the doubled magnitude,
+    /// then emitted 5 bits at a time least-significant-group-first, with the continuation bit
+    /// (`0x20`) set on every group but the last.
+    fn encode_value(&self, value: i32) -> String {
+        let mut vlq_value = if value < 0 {
+            ((-value) as u32) << 1 | 1
+        } else {
+            (value as u32) << 1
+        };
+
+        let mut encoded = String::new();
+        loop {
+            let mut digit = vlq_value & 0b011111;
+            vlq_value >>= 5;
+            if vlq_value > 0 {
+                digit |= 0b100000;
+            }
+            encoded.push(self.alphabet[digit as usize]);
+            if vlq_value == 0 {
+                break;
+            }
+        }
+
+        encoded
+    }
+
+    /// Encodes a full VLQ segment - the inverse of [`VLQDecoder::parse_vlq`].
+    pub fn encode_vlq(&self, values: &[i32]) -> String {
+        values.iter().map(|value| self.encode_value(*value)).collect()
+    }
+}
+
+impl Default for VLQEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Re-applies the `mappings` string's relative-delta encoding (the inverse of
+/// [`SourceMapParser::merge_relative_metadatas`]) to a list of already-resolved, absolute
+/// [`MapMetadata`] segments, producing the `mappings` string that would decode back to them.
+/// `segments` must be sorted by `(line_number, column_number)`; the column delta resets to zero at
+/// the start of every generated line, while `source_index`/`source_line`/`source_column`/
+/// `symbol_index` stay relative across the whole map, same as on decode.
+pub fn build_mappings(segments: &[MapMetadata]) -> String {
+    let encoder = VLQEncoder::new();
+    let mut output = String::new();
+    let mut current_line = 0;
+    let mut last_column = 0;
+    let mut last_source_index: Option<i32> = None;
+    let mut last_source_line: Option<i32> = None;
+    let mut last_source_column: Option<i32> = None;
+    let mut last_symbol_index: Option<i32> = None;
+
+    for segment in segments {
+        while current_line < segment.line_number {
+            output.push(';');
+            current_line += 1;
+            last_column = 0;
+        }
+
+        if !output.is_empty() && !output.ends_with(';') {
+            output.push(',');
+        }
+
+        let mut values = vec![segment.column_number - last_column];
+        last_column = segment.column_number;
+
+        if let (Some(source_index), Some(source_line), Some(source_column)) =
+            (segment.source_index, segment.source_line, segment.source_column)
+        {
+            values.push(source_index - last_source_index.unwrap_or(0));
+            values.push(source_line - last_source_line.unwrap_or(0));
+            values.push(source_column - last_source_column.unwrap_or(0));
+            last_source_index = Some(source_index);
+            last_source_line = Some(source_line);
+            last_source_column = Some(source_column);
+
+            if let Some(symbol_index) = segment.symbol_index {
+                values.push(symbol_index - last_symbol_index.unwrap_or(0));
+                last_symbol_index = Some(symbol_index);
+            }
+        }
+
+        output.push_str(&encoder.encode_vlq(&values));
+    }
+
+    output
+}
+
 struct ValueMask {
     mask: u32,
     right_padding: u32,
@@ -277,14 +533,38 @@ impl Default for VLQDecoder {
 #[derive(Serialize, Deserialize, Debug)]
 struct SourceMapSchema {
     version: i32,
+    #[serde(default)]
     sources: Vec<String>,
+    #[serde(default)]
     names: Vec<String>,
+    #[serde(default)]
     mappings: String,
     #[serde(rename = "sourcesContent")]
     sources_content: Option<Vec<String>>,
     #[serde(rename = "sourceRoot")]
     source_root: Option<String>,
     file: Option<String>,
+    // Present on indexed (sectioned) source maps instead of a single top-level `mappings` - see
+    // https://tc39.es/source-map/#index-source-map.
+    #[serde(default)]
+    sections: Option<Vec<SourceMapSection>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SourceMapSection {
+    offset: SourceMapOffset,
+    // Mutually exclusive with `url` in the spec: either the sub-map is inlined here, or it lives
+    // at an external file this struct only records the location of.
+    #[serde(default)]
+    map: Option<SourceMapSchema>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct SourceMapOffset {
+    line: i32,
+    column: i32,
 }
 
 pub fn make_source_map_paths_absolute(
@@ -297,25 +577,41 @@ pub fn make_source_map_paths_absolute(
         .parent()
         .unwrap_or_else(|| Path::new(""));
 
+    absolutize_schema_sources(&mut source_map, parent_path);
+
+    serde_json::to_string(&source_map)
+}
+
+/// Absolutizes `source_map.sources` in place, then recurses into every section's inline `map` (if
+/// any) so indexed source maps get the same treatment applied to each of their sub-maps.
+fn absolutize_schema_sources(source_map: &mut SourceMapSchema, parent_path: &Path) {
     source_map.sources = source_map
         .sources
         .iter()
-        .map(|source| {
-            let source_path = Path::new(source);
-            if source_path.is_absolute() {
-                source_path.absolutize().unwrap().to_path_buf()
-            } else {
-                parent_path
-                    .join(source_path)
-                    .absolutize()
-                    .unwrap()
-                    .to_path_buf()
-            }
-        })
-        .map(|path| path.to_string_lossy().into_owned())
+        .map(|source| absolutize_source(source, parent_path))
         .collect();
 
-    serde_json::to_string(&source_map)
+    if let Some(sections) = &mut source_map.sections {
+        for section in sections {
+            if let Some(inner) = &mut section.map {
+                absolutize_schema_sources(inner, parent_path);
+            }
+        }
+    }
+}
+
+fn absolutize_source(source: &str, parent_path: &Path) -> String {
+    let source_path = Path::new(source);
+    let absolutized = if source_path.is_absolute() {
+        source_path.absolutize().unwrap().to_path_buf()
+    } else {
+        parent_path
+            .join(source_path)
+            .absolutize()
+            .unwrap()
+            .to_path_buf()
+    };
+    absolutized.to_string_lossy().into_owned()
 }
 
 pub fn update_source_map_path(contents: &str, new_path: &str) -> String {
@@ -331,6 +627,160 @@ pub fn update_source_map_path(contents: &str, new_path: &str) -> String {
     .into_owned()
 }
 
+/// A [`MapMetadata`] lookup with its `source_index`/`symbol_index` already resolved to the
+/// strings they point at in the map's `sources`/`names` tables, plus (when `sourcesContent` was
+/// embedded in the map) the original source line the position falls on - convenient for error
+/// messages and stack trace rewriting, where a bare index is useless without the map around to
+/// resolve it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolvedSymbol {
+    pub source: Option<String>,
+    pub source_line: Option<i32>,
+    pub source_column: Option<i32>,
+    pub name: Option<String>,
+    pub original_line_text: Option<String>,
+}
+
+/// A parsed source map that keeps its `sources`/`names`/`sourcesContent` tables around so callers
+/// can resolve a generated position straight to the strings they name, rather than the bare
+/// indices [`SourceMapParser::resolve`] returns. Unlike calling [`SourceMapParser::parse_source_map`]
+/// directly, [`Self::parse`] also rebuilds the per-line index from the *combined* (all-sections)
+/// metadata, so [`Self::resolve`] is correct for indexed (sectioned) maps too.
+pub struct SourceMap {
+    parser: SourceMapParser,
+    sources: Vec<String>,
+    names: Vec<String>,
+    sources_content: Option<Vec<String>>,
+}
+
+impl SourceMap {
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let schema: SourceMapSchema = serde_json::from_str(contents)
+            .map_err(|e| format!("Failed to parse source map JSON: {:?}", e))?;
+
+        let mut parser = SourceMapParser::new(VLQDecoder::new());
+        let combined = parser.parse_schema(&schema)?;
+        parser.rebuild_lines_from(combined.into_values());
+
+        Ok(Self {
+            parser,
+            sources: schema.sources,
+            names: schema.names,
+            sources_content: schema.sources_content,
+        })
+    }
+
+    /// Same 1-indexed `(line, column)` lookup as [`SourceMapParser::resolve`], but returning the
+    /// resolved source path, original name, and (when available) the original source line text
+    /// instead of bare indices into `sources`/`names`.
+    pub fn resolve(&self, line: i32, column: i32) -> Option<ResolvedSymbol> {
+        let metadata = self.parser.resolve(line, column)?;
+
+        let source = metadata
+            .source_index
+            .and_then(|idx| self.sources.get(idx as usize).cloned());
+        let name = metadata
+            .symbol_index
+            .and_then(|idx| self.names.get(idx as usize).cloned());
+        let original_line_text = match (metadata.source_index, metadata.source_line, &self.sources_content) {
+            (Some(src_idx), Some(src_line), Some(contents)) => contents
+                .get(src_idx as usize)
+                .and_then(|full| full.lines().nth(src_line as usize))
+                .map(|s| s.to_string()),
+            _ => None,
+        };
+
+        Some(ResolvedSymbol {
+            source,
+            source_line: metadata.source_line,
+            source_column: metadata.source_column,
+            name,
+            original_line_text,
+        })
+    }
+
+    /// The map's `sources` table: every module the bundler actually pulled into this chunk's
+    /// module graph, in source-map source order. This is the bundler's own resolved dependency
+    /// list - more reliable than re-deriving it by re-scanning import syntax by hand.
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+}
+
+/// Collapses two maps from a multi-stage build pipeline - `outer` mapping the final generated
+/// output back to some intermediate source, `inner` mapping that intermediate source back to the
+/// earliest original source - into a single map from the final generated output directly to the
+/// earliest original source.
+///
+/// For each of `outer`'s segments, its resolved original position is looked up in `inner` using
+/// the same nearest-segment rule [`SourceMapParser::resolve`] uses, and a new segment is emitted
+/// pairing `outer`'s generated position with `inner`'s resolved original position. `sources` and
+/// `names` entries are deduplicated into the composed map's own tables as they're encountered. A
+/// segment whose original position has no match in `inner` (e.g. `inner` doesn't cover generated
+/// code from a prior build step, such as an injected polyfill) falls back to `outer`'s own
+/// source/line/column, so the composed map still points somewhere rather than dropping the
+/// segment entirely.
+pub fn compose(outer: &SourceMap, inner: &SourceMap) -> SourceMap {
+    let mut composed_sources: Vec<String> = Vec::new();
+    let mut composed_names: Vec<String> = Vec::new();
+    let mut segments: Vec<MapMetadata> = Vec::new();
+
+    for line_segments in &outer.parser.lines {
+        for outer_segment in line_segments {
+            let mut composed = MapMetadata::new(outer_segment.line_number, outer_segment.column_number);
+
+            if let (Some(src_idx), Some(src_line), Some(src_column)) = (
+                outer_segment.source_index,
+                outer_segment.source_line,
+                outer_segment.source_column,
+            ) {
+                if let Some(resolved) = inner.resolve(src_line + 1, src_column + 1) {
+                    if let Some(source) = resolved.source {
+                        composed.source_index = Some(intern(&mut composed_sources, source));
+                    }
+                    composed.source_line = resolved.source_line;
+                    composed.source_column = resolved.source_column;
+                    if let Some(name) = resolved.name {
+                        composed.symbol_index = Some(intern(&mut composed_names, name));
+                    }
+                } else if let Some(source) = outer.sources.get(src_idx as usize) {
+                    composed.source_index = Some(intern(&mut composed_sources, source.clone()));
+                    composed.source_line = Some(src_line);
+                    composed.source_column = Some(src_column);
+                }
+            }
+
+            segments.push(composed);
+        }
+    }
+
+    segments.sort_by_key(|segment| (segment.line_number, segment.column_number));
+    let mappings = build_mappings(&segments);
+
+    let mut parser = SourceMapParser::new(VLQDecoder::new());
+    parser
+        .parse_mapping(&mappings)
+        .expect("mappings built from valid segments should always re-parse");
+
+    SourceMap {
+        parser,
+        sources: composed_sources,
+        names: composed_names,
+        sources_content: None,
+    }
+}
+
+/// Returns `value`'s index in `table`, appending it first if it isn't already present.
+fn intern(table: &mut Vec<String>, value: String) -> i32 {
+    match table.iter().position(|existing| existing == &value) {
+        Some(pos) => pos as i32,
+        None => {
+            table.push(value);
+            (table.len() - 1) as i32
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -466,6 +916,259 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolve_exact_segment_match() {
+        let mut parser = SourceMapParser::new(VLQDecoder::new());
+        // Two segments on generated line 1 (0-indexed 0), at raw columns 13 and 31 (the second
+        // segment's column is relative to the first, per the VLQ spec).
+        parser.parse_mapping("aAYQA,kBAChO").unwrap();
+
+        let resolved = parser.resolve(1, 32).expect("Should resolve an exact hit");
+        assert_eq!(resolved.line_number, 0);
+        assert_eq!(resolved.column_number, 31);
+    }
+
+    #[test]
+    fn test_resolve_nearest_preceding_segment_on_same_line() {
+        let mut parser = SourceMapParser::new(VLQDecoder::new());
+        parser.parse_mapping("aAYQA,kBAChO").unwrap();
+
+        // Column 40 doesn't land on a segment boundary, so it should resolve to whichever
+        // segment on the line starts at or before it - here, the second one at column 31.
+        let resolved = parser.resolve(1, 40).expect("Should resolve nearest segment");
+        assert_eq!(resolved.column_number, 31);
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_preceding_line() {
+        let mut parser = SourceMapParser::new(VLQDecoder::new());
+        // Line 1 (0-indexed 0) has one segment; line 2 is empty.
+        parser.parse_mapping("aAYQA;").unwrap();
+
+        let resolved = parser
+            .resolve(2, 1)
+            .expect("Should fall back to the preceding line's last segment");
+        assert_eq!(resolved.line_number, 0);
+    }
+
+    #[test]
+    fn test_resolve_returns_none_before_first_mapped_line() {
+        let mut parser = SourceMapParser::new(VLQDecoder::new());
+        parser.parse_mapping("aAYQA").unwrap();
+
+        assert_eq!(parser.resolve(0, 1), None);
+    }
+
+    #[test]
+    fn test_encode_vlq_round_trips_with_decoder() {
+        let decoder = VLQDecoder::new();
+        let encoder = VLQEncoder::new();
+
+        let cases = vec![
+            vec![13, 0, 12, 8, 0],
+            vec![1, 0, 0, 0],
+            vec![0, 1, -2738, 0],
+            vec![35, 0, 0, 35],
+        ];
+
+        for values in cases {
+            let encoded = encoder.encode_vlq(&values);
+            assert_eq!(decoder.parse_vlq(&encoded), values);
+        }
+    }
+
+    #[test]
+    fn test_build_mappings_round_trips_through_parse_mapping() {
+        let mappings_str = "aAYQA,kBAChO;GAAA";
+
+        let mut parser = SourceMapParser::new(VLQDecoder::new());
+        let original = parser.parse_mapping(mappings_str).unwrap();
+
+        let mut segments: Vec<MapMetadata> = parser.lines.iter().flatten().cloned().collect();
+        segments.sort_by_key(|segment| (segment.line_number, segment.column_number));
+
+        let rebuilt = build_mappings(&segments);
+
+        let mut reparser = SourceMapParser::new(VLQDecoder::new());
+        let reparsed = reparser.parse_mapping(&rebuilt).unwrap();
+
+        assert_eq!(reparsed, original);
+    }
+
+    #[test]
+    fn test_parse_source_map_flattens_sections() {
+        // Two sections, each a single-segment flat map ("aAYQA" -> column 13), offset by
+        // (line: 0, column: 0) and (line: 1, column: 5) respectively.
+        let contents = r#"{
+            "version": 3,
+            "sections": [
+                {
+                    "offset": {"line": 0, "column": 0},
+                    "map": {
+                        "version": 3,
+                        "sources": ["a.js"],
+                        "names": [],
+                        "mappings": "aAYQA"
+                    }
+                },
+                {
+                    "offset": {"line": 1, "column": 5},
+                    "map": {
+                        "version": 3,
+                        "sources": ["b.js"],
+                        "names": [],
+                        "mappings": "aAYQA"
+                    }
+                }
+            ]
+        }"#;
+
+        let mut parser = SourceMapParser::new(VLQDecoder::new());
+        let combined = parser.parse_source_map(contents).unwrap();
+
+        // First section is unshifted: raw column 13 -> 1-indexed key (1, 14).
+        assert!(combined.contains_key(&(1, 14)));
+        // Second section's single segment is on its own first line, so both the line and column
+        // offsets apply: generated line 1+1=2, raw column 13+5=18 -> 1-indexed key (2, 19).
+        assert!(combined.contains_key(&(2, 19)));
+    }
+
+    #[test]
+    fn test_parse_source_map_skips_url_sections() {
+        let contents = r#"{
+            "version": 3,
+            "sections": [
+                {"offset": {"line": 0, "column": 0}, "url": "external.map.json"}
+            ]
+        }"#;
+
+        let mut parser = SourceMapParser::new(VLQDecoder::new());
+        let combined = parser.parse_source_map(contents).unwrap();
+
+        assert!(combined.is_empty());
+    }
+
+    #[test]
+    fn test_make_source_map_paths_absolute_recurses_into_sections() {
+        let temp_dir = tempdir().unwrap();
+        let original_script_path = temp_dir.path().join("dist/main.js");
+        fs::create_dir_all(original_script_path.parent().unwrap()).unwrap();
+
+        let contents = r#"{
+            "version": 3,
+            "sources": [],
+            "names": [],
+            "mappings": "",
+            "sections": [
+                {
+                    "offset": {"line": 0, "column": 0},
+                    "map": {
+                        "version": 3,
+                        "sources": ["./src/a.js"],
+                        "names": [],
+                        "mappings": ""
+                    }
+                }
+            ]
+        }"#;
+
+        let modified_json =
+            make_source_map_paths_absolute(contents, &original_script_path).unwrap();
+        let modified: SourceMapSchema = serde_json::from_str(&modified_json).unwrap();
+
+        let section_sources = &modified.sections.unwrap()[0].map.as_ref().unwrap().sources;
+        assert_eq!(
+            section_sources[0],
+            temp_dir
+                .path()
+                .join("dist/src/a.js")
+                .to_string_lossy()
+                .into_owned()
+        );
+    }
+
+    #[test]
+    fn test_source_map_resolve_returns_resolved_strings() {
+        let contents = r#"{
+            "version": 3,
+            "sources": ["a.js"],
+            "names": ["foo"],
+            "mappings": "SAAAA",
+            "sourcesContent": ["line zero\nline one\nline two"]
+        }"#;
+
+        let source_map = SourceMap::parse(contents).unwrap();
+
+        // "SAAAA" decodes to [9, 0, 0, 0, 0]: column 9, source_index 0, source_line 0,
+        // source_column 0, symbol_index 0 - the only segment, so the exact hit is at column 10.
+        let resolved = source_map
+            .resolve(1, 10)
+            .expect("Should resolve an exact hit");
+
+        assert_eq!(resolved.source, Some("a.js".to_string()));
+        assert_eq!(resolved.name, Some("foo".to_string()));
+        assert_eq!(resolved.original_line_text, Some("line zero".to_string()));
+    }
+
+    #[test]
+    fn test_source_map_resolve_missing_name_and_content_are_none() {
+        let contents = r#"{
+            "version": 3,
+            "sources": ["a.js"],
+            "names": [],
+            "mappings": "CAAA"
+        }"#;
+
+        let source_map = SourceMap::parse(contents).unwrap();
+        // "CAAA" decodes to [1, 0, 0, 0]: raw column 1 -> 1-indexed column 2.
+        let resolved = source_map.resolve(1, 2).expect("Should resolve");
+
+        assert_eq!(resolved.source, Some("a.js".to_string()));
+        assert_eq!(resolved.name, None);
+        assert_eq!(resolved.original_line_text, None);
+    }
+
+    #[test]
+    fn test_source_map_resolve_works_across_sections() {
+        // Mirrors test_parse_source_map_flattens_sections, but checked through SourceMap::resolve
+        // rather than the raw lookup table, to confirm the per-line index covers every section -
+        // not just the last one processed, which is the pitfall parse_source_map's doc comment
+        // warns about.
+        let contents = r#"{
+            "version": 3,
+            "sections": [
+                {
+                    "offset": {"line": 0, "column": 0},
+                    "map": {
+                        "version": 3,
+                        "sources": ["a.js"],
+                        "names": [],
+                        "mappings": "aAYQA"
+                    }
+                },
+                {
+                    "offset": {"line": 1, "column": 5},
+                    "map": {
+                        "version": 3,
+                        "sources": ["b.js"],
+                        "names": [],
+                        "mappings": "aAYQA"
+                    }
+                }
+            ]
+        }"#;
+
+        let source_map = SourceMap::parse(contents).unwrap();
+
+        // First section's segment: generated line 1, raw column 13.
+        let first = source_map.resolve(1, 14).expect("Should resolve section one");
+        assert_eq!(first.source, Some("a.js".to_string()));
+
+        // Second section's segment: shifted to generated line 2, raw column 13+5=18.
+        let second = source_map.resolve(2, 19).expect("Should resolve section two");
+        assert_eq!(second.source, Some("b.js".to_string()));
+    }
+
     #[test]
     fn test_make_source_map_paths_absolute() {
         let temp_dir = tempdir().unwrap();
@@ -540,4 +1243,91 @@ mod tests {
             );
         }
     }
+
+    fn source_map_from_segments(
+        sources: &[&str],
+        names: &[&str],
+        segments: &[MapMetadata],
+    ) -> SourceMap {
+        let contents = serde_json::json!({
+            "version": 3,
+            "sources": sources,
+            "names": names,
+            "mappings": build_mappings(segments),
+        });
+
+        SourceMap::parse(&contents.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_compose_chains_through_intermediate_source() {
+        // outer: bundled output, generated column 9 on line 1, maps to intermediate.js:1:1.
+        let outer = source_map_from_segments(
+            &["intermediate.js"],
+            &["onClick"],
+            &[MapMetadata {
+                line_number: 0,
+                column_number: 9,
+                source_index: Some(0),
+                source_line: Some(0),
+                source_column: Some(0),
+                symbol_index: Some(0),
+            }],
+        );
+
+        // inner: the intermediate.js -> original.ts map, with a segment exactly at (0, 0).
+        let inner = source_map_from_segments(
+            &["original.ts"],
+            &["clickHandler"],
+            &[MapMetadata {
+                line_number: 0,
+                column_number: 0,
+                source_index: Some(0),
+                source_line: Some(5),
+                source_column: Some(2),
+                symbol_index: Some(0),
+            }],
+        );
+
+        let composed = compose(&outer, &inner);
+
+        let resolved = composed
+            .resolve(1, 10)
+            .expect("Should resolve the composed generated position");
+
+        assert_eq!(resolved.source, Some("original.ts".to_string()));
+        assert_eq!(resolved.source_line, Some(5));
+        assert_eq!(resolved.source_column, Some(2));
+        assert_eq!(resolved.name, Some("clickHandler".to_string()));
+    }
+
+    #[test]
+    fn test_compose_falls_back_to_outer_when_inner_has_no_match() {
+        // outer points at intermediate.js:1:1, but inner has no segments at all - e.g. an
+        // injected polyfill that the intermediate map never covered.
+        let outer = source_map_from_segments(
+            &["intermediate.js"],
+            &[],
+            &[MapMetadata {
+                line_number: 0,
+                column_number: 9,
+                source_index: Some(0),
+                source_line: Some(0),
+                source_column: Some(0),
+                symbol_index: None,
+            }],
+        );
+
+        let inner = source_map_from_segments(&[], &[], &[]);
+
+        let composed = compose(&outer, &inner);
+
+        let resolved = composed
+            .resolve(1, 10)
+            .expect("Should still resolve via the outer fallback");
+
+        assert_eq!(resolved.source, Some("intermediate.js".to_string()));
+        assert_eq!(resolved.source_line, Some(0));
+        assert_eq!(resolved.source_column, Some(0));
+    }
 }
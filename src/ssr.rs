@@ -26,29 +26,120 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
-use crate::errors::AppError;
+use crate::cpu_watchdog::CpuWatchdog;
+use crate::errors::{AppError, JsError, JsStackFrame};
+use crate::inspector::Inspector;
 use crate::logging::StdoutWrapper;
+use crate::module_loader::ModuleLoader;
+use crate::ops::OpRegistry;
+use crate::source_map::{ResolvedSymbol, SourceMap};
 use crate::timeout;
-use std::collections::HashMap;
+use regex::{Captures, Regex};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone)]
 pub struct Ssr<'a> {
     // TODO: Check if better Box<str> instead of String
     source: String,
     entry_point: &'a str,
+    // When present, every render starts its isolate from this precompiled heap instead of
+    // recompiling `source`'s static prefix (polyfills + framework runtime) from scratch - see
+    // `crate::snapshot`. `source` should then only contain the page-specific entry point.
+    snapshot: Option<Arc<[u8]>>,
+    // When present, installed as `globalThis.__ops` before the entry point runs - see
+    // `crate::ops::OpRegistry` and `Self::install_ops`.
+    ops: Option<Arc<OpRegistry>>,
+    // When both are present, `render` samples the render thread's *consumed CPU time* (not wall
+    // time) against these budgets for the render's duration - see `crate::cpu_watchdog`.
+    cpu_soft_budget: Option<Duration>,
+    cpu_hard_budget: Option<Duration>,
+    // When present, tags every `console.*` line this render's isolate emits - see
+    // `Self::with_request_id` and `inject_logger`.
+    request_id: Option<String>,
+    // When present, `console.*` output is buffered here instead of going to the global
+    // `StdoutWrapper` stdout sink - see `Self::with_console_capture` and `Self::take_console_output`.
+    console_capture: Option<Arc<Mutex<Vec<u8>>>>,
+}
+
+impl<'a> std::fmt::Debug for Ssr<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ssr")
+            .field("source", &self.source)
+            .field("entry_point", &self.entry_point)
+            .field("snapshot", &self.snapshot)
+            .field("ops", &self.ops.as_ref().map(|_| "<OpRegistry>"))
+            .field("cpu_soft_budget", &self.cpu_soft_budget)
+            .field("cpu_hard_budget", &self.cpu_hard_budget)
+            .field("request_id", &self.request_id)
+            .field(
+                "console_capture",
+                &self.console_capture.as_ref().map(|_| "<buffer>"),
+            )
+            .finish()
+    }
+}
+
+impl<'a> PartialEq for Ssr<'a> {
+    // `OpRegistry` holds trait objects that can't be compared, so two instances are only
+    // considered equal in that respect if they either both have ops installed or both don't. The
+    // same goes for `console_capture`'s shared buffer.
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.entry_point == other.entry_point
+            && self.snapshot == other.snapshot
+            && self.ops.is_some() == other.ops.is_some()
+            && self.cpu_soft_budget == other.cpu_soft_budget
+            && self.cpu_hard_budget == other.cpu_hard_budget
+            && self.request_id == other.request_id
+            && self.console_capture.is_some() == other.console_capture.is_some()
+    }
 }
 
 struct LoggerData {
     console_type: String,
     stdout: Arc<Mutex<dyn Write + 'static>>,
+    request_id: Option<String>,
 }
 
 // Ensure that LoggerData can be sent safely across threads.
 unsafe impl Send for LoggerData {}
 
+struct OpData {
+    name: String,
+    registry: Arc<OpRegistry>,
+}
+
+// Ensure that OpData can be sent safely across threads.
+unsafe impl Send for OpData {}
+
+/// Converts a V8 value to its `serde_json::Value` equivalent by round-tripping it through V8's
+/// own JSON serializer - simpler and less error-prone than walking the value by hand, at the cost
+/// of only supporting JSON-representable values (no functions, symbols, etc., which ops have no
+/// use for anyway).
+fn v8_value_to_json(scope: &mut v8::HandleScope, value: v8::Local<v8::Value>) -> serde_json::Value {
+    let Some(json_string) = v8::json::stringify(scope, value) else {
+        return serde_json::Value::Null;
+    };
+    let json_string = json_string.to_rust_string_lossy(scope);
+    serde_json::from_str(&json_string).unwrap_or(serde_json::Value::Null)
+}
+
+/// Inverse of `v8_value_to_json`: serializes `value` to a JSON string in Rust, then has V8 parse
+/// it back into a native value in `scope`.
+fn json_to_v8_value<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    value: &serde_json::Value,
+) -> Option<v8::Local<'s, v8::Value>> {
+    let json_string = serde_json::to_string(value).ok()?;
+    let v8_string = v8::String::new(scope, &json_string)?;
+    v8::json::parse(scope, v8_string)
+}
+
 impl<'a> Ssr<'a> {
     /// Create an instance of the Ssr struct instanciate the v8 platform as well.
     pub fn new(source: String, entry_point: &'a str) -> Self {
@@ -57,10 +148,89 @@ impl<'a> Ssr<'a> {
         Ssr {
             source,
             entry_point,
+            snapshot: None,
+            ops: None,
+            cpu_soft_budget: None,
+            cpu_hard_budget: None,
+            request_id: None,
+            console_capture: None,
+        }
+    }
+
+    /// Same as [`Self::new`], but `render_to_string` builds its isolate from `snapshot` (see
+    /// `crate::snapshot::build_snapshot`/`cached_snapshot`) instead of a default, empty one.
+    /// `source` should contain only the page-specific entry point - everything baked into the
+    /// snapshot is already compiled and executed by the time it runs.
+    pub fn with_snapshot(source: String, entry_point: &'a str, snapshot: Arc<[u8]>) -> Self {
+        Self::init_platform();
+
+        Ssr {
+            source,
+            entry_point,
+            snapshot: Some(snapshot),
+            ops: None,
+            cpu_soft_budget: None,
+            cpu_hard_budget: None,
+            request_id: None,
+            console_capture: None,
         }
     }
 
-    fn init_platform() {
+    /// Registers `ops` so that `globalThis.__ops.<name>(...)` is callable from `source` during
+    /// render - see [`OpRegistry`]. Lets render code fetch data, read config, or call host
+    /// services synchronously instead of requiring everything be pre-serialized into
+    /// `render_to_string`'s single `params` string.
+    pub fn with_ops(mut self, ops: OpRegistry) -> Self {
+        self.ops = Some(Arc::new(ops));
+        self
+    }
+
+    /// Polls the render thread's *consumed CPU time* (not wall time) against `soft_budget`/
+    /// `hard_budget` for the duration of the render - see [`crate::cpu_watchdog::CpuWatchdog`].
+    /// Crossing `soft_budget` only logs a warning; crossing `hard_budget` forcibly terminates the
+    /// isolate and the render returns `AppError::HardTimeoutError`. Unlike
+    /// `crate::timeout::run_thread_with_timeout`'s wall-clock deadline, this isn't fooled by a
+    /// render that's merely blocked (GC, a busy host) rather than actually looping.
+    pub fn with_cpu_budget(mut self, soft_budget: Duration, hard_budget: Duration) -> Self {
+        self.cpu_soft_budget = Some(soft_budget);
+        self.cpu_hard_budget = Some(hard_budget);
+        self
+    }
+
+    /// Tags every `console.*` call this render makes - both the line written to the stdout sink
+    /// and the `log` crate record emitted alongside it (see [`Self::inject_logger`]) - with
+    /// `request_id`. Useful when several renders share `StdoutWrapper`'s process-wide stdout sink
+    /// and their interleaved output otherwise can't be attributed back to the render that
+    /// produced it.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Routes this render's `console.*` output into a private in-memory buffer instead of the
+    /// global `StdoutWrapper` stdout sink, so a caller can retrieve exactly the lines this render
+    /// produced via [`Self::take_console_output`] rather than grepping a shared, interleaved
+    /// stdout stream. `console.*` calls are still mirrored through the `log` crate regardless of
+    /// this setting - see [`Self::inject_logger`].
+    pub fn with_console_capture(mut self) -> Self {
+        self.console_capture = Some(Arc::new(Mutex::new(Vec::new())));
+        self
+    }
+
+    /// Drains and returns everything `console.*` wrote during the last render, if
+    /// [`Self::with_console_capture`] was set - `None` if capture wasn't enabled (output went to
+    /// the global stdout sink instead). Draining resets the buffer, so a caller re-rendering the
+    /// same `Ssr` sees only each render's own output rather than an ever-growing log.
+    pub fn take_console_output(&self) -> Option<String> {
+        let buffer = self.console_capture.as_ref()?;
+        let mut guard = buffer.lock().expect("Failed to lock mutex");
+        let bytes = std::mem::take(&mut *guard);
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Initializes the process-wide V8 platform. Idempotent - safe to call from anywhere that's
+    /// about to create an isolate (e.g. [`crate::snapshot::build_snapshot`]), not just `Ssr`.
+    pub(crate) fn init_platform() {
         lazy_static! {
           static ref INIT_PLATFORM: () = {
               // Include ICU data file.
@@ -84,15 +254,61 @@ impl<'a> Ssr<'a> {
             self.source.clone(),
             self.entry_point,
             params,
-            StdoutWrapper::new().get_arc(),
+            self.console_sink(),
+            self.snapshot.clone(),
+            self.ops.clone(),
+            false,
+            self.cpu_soft_budget,
+            self.cpu_hard_budget,
+            self.request_id.clone(),
         )
     }
 
+    /// Same as [`Self::render_to_string`], but the entry function's return value may be a
+    /// `Promise` instead of a plain string - e.g. React 18's `renderToString` wrapped around a
+    /// `Suspense` boundary, or any `async` top-level SSR function. Queued microtasks are pumped
+    /// via `perform_microtask_checkpoint` until the promise settles, then its resolved value (or
+    /// rejection reason) is handled the same way a synchronous return value would be.
+    pub fn render_to_string_async(&self, params: Option<&str>) -> Result<String, AppError> {
+        Self::render(
+            self.source.clone(),
+            self.entry_point,
+            params,
+            self.console_sink(),
+            self.snapshot.clone(),
+            self.ops.clone(),
+            true,
+            self.cpu_soft_budget,
+            self.cpu_hard_budget,
+            self.request_id.clone(),
+        )
+    }
+
+    /// The `console.*` destination for this render: `self.console_capture`'s private buffer if
+    /// [`Self::with_console_capture`] was set, otherwise the global [`StdoutWrapper`] sink.
+    fn console_sink(&self) -> Arc<Mutex<dyn Write + 'static>> {
+        match &self.console_capture {
+            Some(buffer) => buffer.clone(),
+            None => StdoutWrapper::new().get_arc(),
+        }
+    }
+
+    /// How often `cpu_soft_budget`/`cpu_hard_budget` are checked against the render thread's
+    /// accumulated CPU time - see [`crate::cpu_watchdog::CpuWatchdog`].
+    const CPU_WATCHDOG_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    #[allow(clippy::too_many_arguments)]
     fn render(
         source: String,
         entry_point: &str,
         params: Option<&str>,
         stdout: Arc<Mutex<dyn Write + 'static>>,
+        snapshot: Option<Arc<[u8]>>,
+        ops: Option<Arc<OpRegistry>>,
+        resolve_promises: bool,
+        cpu_soft_budget: Option<Duration>,
+        cpu_hard_budget: Option<Duration>,
+        request_id: Option<String>,
     ) -> Result<String, AppError> {
         /*
          * Main entrypoint for rendering, takes a source string (containing one or many functions) and
@@ -100,13 +316,51 @@ impl<'a> Ssr<'a> {
          * a string.
          */
         // let isolate_params = v8::CreateParams::default().heap_limits(0, 2000 * 1024 * 1024);
-        let isolate = &mut v8::Isolate::new(Default::default());
+        let create_params = match snapshot {
+            Some(blob) => v8::CreateParams::default().snapshot_blob(blob.to_vec().into_boxed_slice()),
+            None => v8::CreateParams::default(),
+        };
+        let isolate = &mut v8::Isolate::new(create_params);
+
+        // Kept alive for the rest of the render - its `Drop` stops the monitor thread on every
+        // return path (success or any early-return error) without each having to remember to.
+        let _watchdog_guard = match (cpu_soft_budget, cpu_hard_budget) {
+            (Some(soft_budget), Some(hard_budget)) => {
+                let isolate_handle = isolate.thread_safe_handle();
+                Some(
+                    CpuWatchdog::new(soft_budget, hard_budget, Self::CPU_WATCHDOG_POLL_INTERVAL)
+                        .watch(move || {
+                            isolate_handle.terminate_execution();
+                        }),
+                )
+            }
+            _ => None,
+        };
+
         let handle_scope = &mut v8::HandleScope::new(isolate);
         let mut context = v8::Context::new(handle_scope);
         let scope = &mut v8::ContextScope::new(handle_scope, context);
 
+        // When MOUNTAINEER_INSPECT is set, block here until a CDP debugger attaches - see
+        // `crate::inspector`. Held for the rest of the render so its session stays connected.
+        let _inspector_session = match crate::inspector::inspect_addr() {
+            Some(addr) => match Inspector::attach(scope, context, &addr) {
+                Ok(session) => Some(session),
+                Err(err) => {
+                    log::warn!("Failed to attach CDP inspector on {addr}: {err}");
+                    None
+                }
+            },
+            None => None,
+        };
+
         // Add logging support
-        Self::inject_logger(&mut context, scope, stdout);
+        Self::inject_logger(&mut context, scope, stdout, request_id);
+
+        // Install any registered ops as globalThis.__ops before the entry point runs
+        if let Some(registry) = ops {
+            Self::install_ops(&mut context, scope, registry);
+        }
 
         // Encapsulate all V8 operations that might throw exceptions within this TryCatch block
         let try_catch = &mut v8::TryCatch::new(scope);
@@ -125,28 +379,29 @@ impl<'a> Ssr<'a> {
         let script = if let Some(s) = v8::Script::compile(try_catch, code, None) {
             s
         } else {
-            return Err(AppError::V8ExceptionError(Self::extract_exception_message(
-                try_catch,
-                "Script compilation failed",
-            )));
+            return Err(AppError::V8ExceptionError(
+                Self::extract_exception_message(try_catch, "Script compilation failed", &source)
+                    .into(),
+            ));
         };
 
         let result = if let Some(r) = script.run(try_catch) {
             r
         } else {
-            return Err(AppError::V8ExceptionError(Self::extract_exception_message(
+            return Err(Self::hard_timeout_or_js_error(
                 try_catch,
                 "Script execution failed",
-            )));
+                &source,
+            ));
         };
 
         let object = if let Some(obj) = result.to_object(try_catch) {
             obj
         } else {
-            return Err(AppError::V8ExceptionError(Self::extract_exception_message(
-                try_catch,
-                "Result is not an object",
-            )));
+            return Err(AppError::V8ExceptionError(
+                Self::extract_exception_message(try_catch, "Result is not an object", &source)
+                    .into(),
+            ));
         };
 
         // Assuming `create_fn_map` exists and properly implemented
@@ -163,15 +418,22 @@ impl<'a> Ssr<'a> {
             let key_str = key; // Assuming key is already a Rust String
             let result = func.call(try_catch, object.into(), &[params_v8]);
             if try_catch.has_caught() {
-                return Err(AppError::V8ExceptionError(Self::extract_exception_message(
+                return Err(Self::hard_timeout_or_js_error(
                     try_catch,
                     &format!("Error calling function '{}'", key_str),
-                )));
+                    &source,
+                ));
             }
 
-            let result_str = result
-                .expect("Function call did not return a value")
-                .to_rust_string_lossy(try_catch);
+            let result = result.expect("Function call did not return a value");
+
+            let result = if resolve_promises {
+                Self::await_promise(try_catch, result, &source, &key_str)?
+            } else {
+                result
+            };
+
+            let result_str = result.to_rust_string_lossy(try_catch);
 
             rendered.push_str(&result_str);
         }
@@ -179,10 +441,27 @@ impl<'a> Ssr<'a> {
         Ok(rendered)
     }
 
+    /// The `log` crate target `console.*` calls are tagged with - starts with `mountaineer` so it
+    /// passes through `init_logger`'s `filter(Some("mountaineer"), ...)`.
+    const CONSOLE_LOG_TARGET: &'static str = "mountaineer::ssr_console";
+
+    /// Maps a `console.<method>` name to the `log` crate level it's mirrored at - `warn`/`error`
+    /// keep their severity, `debug` maps to `Debug`, and everything else (`log`, `info`) is
+    /// treated as informational.
+    fn console_log_level(console_type: &str) -> log::Level {
+        match console_type {
+            "error" => log::Level::Error,
+            "warn" => log::Level::Warn,
+            "debug" => log::Level::Debug,
+            _ => log::Level::Info,
+        }
+    }
+
     fn inject_logger(
         context: &mut v8::Local<'_, v8::Context>,
         scope: &mut v8::ContextScope<'_, v8::HandleScope<'_>>,
         stdout: Arc<Mutex<dyn Write + 'static>>,
+        request_id: Option<String>,
     ) {
         let console_types = vec!["log", "warn", "info", "debug", "error"];
         let global = context.global(scope);
@@ -201,6 +480,7 @@ impl<'a> Ssr<'a> {
             let logger_data = LoggerData {
                 console_type: console_type.to_string(),
                 stdout: stdout.clone(),
+                request_id: request_id.clone(),
             };
             let logger_data_external =
                 v8::External::new(scope, Box::into_raw(Box::new(logger_data)) as *mut _);
@@ -236,12 +516,36 @@ impl<'a> Ssr<'a> {
                         .join(" ");
 
                     let mut stdout_lock = logger_data.stdout.lock().unwrap();
-                    writeln!(
-                        stdout_lock,
-                        "ssr console [{}]: {}",
-                        logger_data.console_type, log_message
-                    )
+                    match &logger_data.request_id {
+                        Some(request_id) => writeln!(
+                            stdout_lock,
+                            "ssr console [{}][{}]: {}",
+                            request_id, logger_data.console_type, log_message
+                        ),
+                        None => writeln!(
+                            stdout_lock,
+                            "ssr console [{}]: {}",
+                            logger_data.console_type, log_message
+                        ),
+                    }
                     .expect("Failed to write to stdout");
+                    drop(stdout_lock);
+
+                    // Also mirror console.warn/error/debug (and log/info, downgraded to `Info`)
+                    // through the `log` crate, so SSR-side logging still flows through whatever
+                    // `env_logger` sink `init_logger` configured instead of only ever reaching
+                    // this render's stdout sink.
+                    let level = Self::console_log_level(&logger_data.console_type);
+                    match &logger_data.request_id {
+                        Some(request_id) => log::log!(
+                            target: Self::CONSOLE_LOG_TARGET,
+                            level,
+                            "[{}] {}",
+                            request_id,
+                            log_message
+                        ),
+                        None => log::log!(target: Self::CONSOLE_LOG_TARGET, level, "{}", log_message),
+                    }
 
                     ret_val.set_undefined();
                 },
@@ -255,9 +559,124 @@ impl<'a> Ssr<'a> {
         }
     }
 
+    /// If `value` is a `Promise`, pumps the isolate's microtask queue until it settles and
+    /// returns its resolved value (or an error built from its rejection reason); otherwise
+    /// returns `value` unchanged. Used by `render` when called in async mode, so a `Suspense`- or
+    /// data-fetching-driven component can resolve before its string is read out.
+    fn await_promise<'b>(
+        try_catch: &mut v8::TryCatch<'b, v8::HandleScope>,
+        value: v8::Local<'b, v8::Value>,
+        source: &str,
+        fn_name: &str,
+    ) -> Result<v8::Local<'b, v8::Value>, AppError> {
+        if !value.is_promise() {
+            return Ok(value);
+        }
+
+        let promise = unsafe { v8::Local::<v8::Promise>::cast(value) };
+
+        // A pending promise that never settles on its own (e.g. stuck on a data fetch) would pump
+        // microtasks forever once `CpuWatchdog` terminates the isolate mid-pump - termination
+        // doesn't make the promise settle, it just stops script execution. Check every iteration
+        // so that case bails out with a hard-timeout error instead of livelocking.
+        while promise.state() == v8::PromiseState::Pending {
+            if try_catch.has_terminated() {
+                return Err(Self::hard_timeout_or_js_error(
+                    try_catch,
+                    &format!("Error calling function '{}'", fn_name),
+                    source,
+                ));
+            }
+            try_catch.perform_microtask_checkpoint();
+        }
+
+        match promise.state() {
+            v8::PromiseState::Fulfilled => Ok(promise.result(try_catch)),
+            v8::PromiseState::Rejected => {
+                let reason = promise.result(try_catch);
+                let message = reason.to_rust_string_lossy(try_catch);
+                let remapped = Self::remap_stack_trace(&message, source);
+                Err(AppError::V8ExceptionError(
+                    format!(
+                        "Error calling function '{}': promise rejected with {}",
+                        fn_name, remapped
+                    )
+                    .into(),
+                ))
+            }
+            v8::PromiseState::Pending => unreachable!("checkpoint loop only exits once settled"),
+        }
+    }
+
+    /// Installs every op in `registry` as `globalThis.__ops.<name>`, so the entry point can call
+    /// back into Rust synchronously. Arguments are JSON-bridged via `v8::json::stringify` and the
+    /// op's return value via `v8::json::parse` (see `v8_value_to_json`/`json_to_v8_value`) -
+    /// `OpRegistry` only speaks `serde_json::Value`, not raw V8 values. An op that returns `Err`
+    /// throws a JS exception carrying its message, same as any other host-triggered failure.
+    fn install_ops(
+        context: &mut v8::Local<'_, v8::Context>,
+        scope: &mut v8::ContextScope<'_, v8::HandleScope<'_>>,
+        registry: Arc<OpRegistry>,
+    ) {
+        let global = context.global(scope);
+        let ops_obj = v8::ObjectTemplate::new(scope).new_instance(scope).unwrap();
+        let ops_key = v8::String::new(scope, "__ops").unwrap();
+        global.set(scope, ops_key.into(), ops_obj.into());
+
+        let names: Vec<String> = registry.names().cloned().collect();
+
+        for name in names {
+            let op_data = OpData {
+                name: name.clone(),
+                registry: registry.clone(),
+            };
+            let op_data_external =
+                v8::External::new(scope, Box::into_raw(Box::new(op_data)) as *mut _);
+
+            // Same v8::External pattern as `inject_logger`'s LoggerData - see the comment there.
+            let op_fn = v8::Function::builder(
+                move |scope: &mut v8::HandleScope,
+                      args: v8::FunctionCallbackArguments,
+                      mut ret_val: v8::ReturnValue| {
+                    let data = args.data();
+                    let op_data = if data.is_external() {
+                        let external = unsafe { v8::Local::<v8::External>::cast(data) };
+                        let op_data_ptr = external.value();
+                        unsafe { &*(op_data_ptr as *const OpData) }
+                    } else {
+                        panic!("Expected op data to be passed as external data");
+                    };
+
+                    let json_args: Vec<serde_json::Value> = (0..args.length())
+                        .map(|i| v8_value_to_json(scope, args.get(i)))
+                        .collect();
+
+                    match op_data.registry.call(&op_data.name, &json_args) {
+                        Ok(value) => match json_to_v8_value(scope, &value) {
+                            Some(v8_value) => ret_val.set(v8_value),
+                            None => ret_val.set_undefined(),
+                        },
+                        Err(err) => {
+                            let message = v8::String::new(scope, &err.to_string()).unwrap();
+                            let exception = v8::Exception::error(scope, message);
+                            scope.throw_exception(exception);
+                        }
+                    }
+                },
+            )
+            .data(op_data_external.into())
+            .build(scope)
+            .unwrap();
+
+            let name_key = v8::String::new(scope, &name).unwrap();
+            ops_obj.set(scope, name_key.into(), op_fn.into());
+        }
+    }
+
     fn extract_exception_message(
         try_catch: &mut v8::TryCatch<v8::HandleScope>,
         user_msg: &str,
+        source: &str,
     ) -> String {
         if let Some(exception) = try_catch.exception() {
             let exceptions = try_catch.stack_trace();
@@ -268,7 +687,8 @@ impl<'a> Ssr<'a> {
 
             // Directly use try_catch to get the stack trace if available
             let maybe_stack = exceptions.map_or_else(String::new, |trace| {
-                format!("\nStack: {}", trace.to_rust_string_lossy(&mut scope))
+                let raw_trace = trace.to_rust_string_lossy(&mut scope);
+                format!("\nStack: {}", Self::remap_stack_trace(&raw_trace, source))
             });
 
             format!("{}: {}{}", user_msg, msg, maybe_stack)
@@ -278,6 +698,191 @@ impl<'a> Ssr<'a> {
         }
     }
 
+    /// Same flattened, source-map-remapped message as `extract_exception_message` (so
+    /// `Display`-only callers see no change), plus the thrown value's constructor name and a
+    /// structured stack frame list pulled via `v8::Exception::create_message`'s
+    /// `v8::StackTrace`, for callers (namely the PyO3 boundary) that want to classify the error
+    /// or walk its frames instead of pattern matching a string. When the
+    /// `MOUNTAINEER_SSR_RAW_STACK` env var is set (same style as `MOUNTAINEER_LOG_LEVEL` in
+    /// `crate::logging::init_logger`), the unremapped trace - still pointing into the bundled
+    /// output rather than original source - is kept around in `raw_stack` for debugging the
+    /// remapping itself.
+    fn build_js_error(
+        try_catch: &mut v8::TryCatch<v8::HandleScope>,
+        user_msg: &str,
+        source: &str,
+    ) -> JsError {
+        let message = Self::extract_exception_message(try_catch, user_msg, source);
+        let raw_trace_value = try_catch.stack_trace();
+
+        let Some(exception) = try_catch.exception() else {
+            return JsError {
+                class_name: None,
+                message,
+                frames: Vec::new(),
+                raw_stack: None,
+            };
+        };
+
+        let scope = &mut v8::HandleScope::new(try_catch);
+
+        let class_name = exception.to_object(scope).and_then(|obj| {
+            let ctor_key = v8::String::new(scope, "constructor")?;
+            let ctor = obj.get(scope, ctor_key.into())?.to_object(scope)?;
+            let name_key = v8::String::new(scope, "name")?;
+            let name_value = ctor.get(scope, name_key.into())?;
+            Some(name_value.to_rust_string_lossy(scope))
+        });
+
+        // Loaded once up front (rather than per frame) and reused for every frame's lookup, same
+        // source map `extract_exception_message`'s string-based remapping above already used for
+        // this exception - structured frame data shouldn't point at bundled positions while the
+        // flattened message string right next to it points at original source.
+        let source_map = Self::load_source_map(source);
+
+        let v8_message = v8::Exception::create_message(scope, exception);
+        let frames = v8_message
+            .get_stack_trace(scope)
+            .map(|stack_trace| {
+                (0..stack_trace.get_frame_count())
+                    .filter_map(|i| stack_trace.get_frame(scope, i))
+                    .map(|frame| {
+                        let raw_line = frame.get_line_number();
+                        let raw_column = frame.get_column();
+
+                        let resolved = source_map
+                            .as_ref()
+                            .and_then(|map| map.resolve(raw_line, raw_column));
+
+                        let file_name = frame
+                            .get_script_name(scope)
+                            .map(|s| s.to_rust_string_lossy(scope));
+
+                        match resolved {
+                            Some(ResolvedSymbol {
+                                source: Some(original_source),
+                                source_line: Some(source_line),
+                                source_column: Some(source_column),
+                                ..
+                            }) => JsStackFrame {
+                                function_name: frame
+                                    .get_function_name(scope)
+                                    .map(|s| s.to_rust_string_lossy(scope)),
+                                file_name: Some(original_source),
+                                line: Some(source_line + 1),
+                                column: Some(source_column + 1),
+                            },
+                            _ => JsStackFrame {
+                                function_name: frame
+                                    .get_function_name(scope)
+                                    .map(|s| s.to_rust_string_lossy(scope)),
+                                file_name,
+                                line: Some(raw_line),
+                                column: Some(raw_column),
+                            },
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let raw_stack = if std::env::var("MOUNTAINEER_SSR_RAW_STACK").is_ok() {
+            raw_trace_value.map(|trace| trace.to_rust_string_lossy(scope))
+        } else {
+            None
+        };
+
+        JsError {
+            class_name,
+            message,
+            frames,
+            raw_stack,
+        }
+    }
+
+    /// Same decision `render`'s error paths need at every call/execution site that could run for
+    /// a while: if `try_catch` caught an exception because `CpuWatchdog` forcibly terminated the
+    /// isolate (a hard CPU budget was exceeded), that surfaces as `AppError::HardTimeoutError`
+    /// instead of the usual `V8ExceptionError` - the "forcibly terminated" value V8 throws isn't a
+    /// real JS exception `build_js_error`'s source-mapped message machinery has anything useful to
+    /// say about.
+    fn hard_timeout_or_js_error(
+        try_catch: &mut v8::TryCatch<v8::HandleScope>,
+        user_msg: &str,
+        source: &str,
+    ) -> AppError {
+        if try_catch.has_terminated() {
+            AppError::HardTimeoutError(format!("{} (CPU hard budget exceeded)", user_msg))
+        } else {
+            AppError::V8ExceptionError(Self::build_js_error(try_catch, user_msg, source))
+        }
+    }
+
+    /// Rewrites every `<anonymous>:line:column` frame in a raw V8 stack trace to point at its
+    /// original source location, using a source map discovered in `source` (either embedded as a
+    /// trailing `//# sourceMappingURL=data:application/json;...;base64,...` comment, or a sibling
+    /// `.map` file referenced by a plain path). Frames are left untouched when no map is found, or
+    /// when a given frame's position has no mapping - e.g. code the bundler injected itself.
+    fn remap_stack_trace(raw_trace: &str, source: &str) -> String {
+        let Some(source_map) = Self::load_source_map(source) else {
+            return raw_trace.to_string();
+        };
+
+        lazy_static! {
+            static ref FRAME_LOCATION_RE: Regex = Regex::new(r"<anonymous>:(\d+):(\d+)").unwrap();
+        }
+
+        FRAME_LOCATION_RE
+            .replace_all(raw_trace, |caps: &Captures| {
+                let line: i32 = caps[1].parse().unwrap_or_default();
+                let column: i32 = caps[2].parse().unwrap_or_default();
+
+                match source_map.resolve(line, column) {
+                    Some(ResolvedSymbol {
+                        source: Some(source),
+                        source_line,
+                        source_column,
+                        ..
+                    }) => format!(
+                        "{}:{}:{}",
+                        source,
+                        source_line.unwrap_or_default() + 1,
+                        source_column.unwrap_or_default() + 1,
+                    ),
+                    _ => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+
+    /// Locates and parses the source map for `source`, if any. The standard
+    /// `//# sourceMappingURL=...` comment is read from wherever it last appears (matching how
+    /// bundlers append it), and its target is either an inline `data:application/json;base64,...`
+    /// URI or a path to a sibling `.map` file read straight off disk.
+    fn load_source_map(source: &str) -> Option<SourceMap> {
+        lazy_static! {
+            static ref SOURCE_MAP_URL_RE: Regex =
+                Regex::new(r"//[#@]\s*sourceMappingURL=(\S+)").unwrap();
+        }
+
+        let url = SOURCE_MAP_URL_RE
+            .captures_iter(source)
+            .last()?
+            .get(1)?
+            .as_str();
+
+        let contents = if let Some(encoded) = url
+            .strip_prefix("data:application/json;charset=utf-8;base64,")
+            .or_else(|| url.strip_prefix("data:application/json;base64,"))
+        {
+            String::from_utf8(base64_decode(encoded)?).ok()?
+        } else {
+            std::fs::read_to_string(url).ok()?
+        };
+
+        SourceMap::parse(&contents).ok()
+    }
+
     fn create_fn_map<'b>(
         scope: &mut v8::TryCatch<'b, v8::HandleScope>,
         object: v8::Local<v8::Object>,
@@ -310,6 +915,271 @@ impl<'a> Ssr<'a> {
 
         fn_map
     }
+
+    /// Renders the default export of the ES module graph rooted at `entry_specifier`, resolved
+    /// and loaded through `loader`. Unlike [`Self::render_to_string`], which concatenates a
+    /// pre-bundled `source` string and compiles it as a plain script, this compiles each module
+    /// with `v8::script_compiler::compile_module` and instantiates the real dependency graph - so
+    /// unbundled, per-page ESM (e.g. straight TSX-compiler output) can be rendered directly,
+    /// without a bundler pass first. The entry module's default export is called with `params` the
+    /// same way `render` calls a script's entry-point functions.
+    pub fn render_module(
+        loader: Arc<dyn ModuleLoader>,
+        entry_specifier: &str,
+        params: Option<&str>,
+    ) -> Result<String, AppError> {
+        Self::init_platform();
+
+        let isolate = &mut v8::Isolate::new(v8::CreateParams::default());
+        let handle_scope = &mut v8::HandleScope::new(isolate);
+        let mut context = v8::Context::new(handle_scope);
+        let scope = &mut v8::ContextScope::new(handle_scope, context);
+
+        Self::inject_logger(&mut context, scope, StdoutWrapper::new().get_arc(), None);
+
+        let state = Rc::new(RefCell::new(ModuleMapState {
+            loader,
+            modules: HashMap::new(),
+            specifiers: HashMap::new(),
+            in_progress: HashSet::new(),
+        }));
+        scope.set_slot(state.clone());
+
+        let try_catch = &mut v8::TryCatch::new(scope);
+
+        let entry_module = match Self::load_module_graph(try_catch, &state, entry_specifier) {
+            Ok(module) => module,
+            Err(err) => {
+                return Err(AppError::V8ExceptionError(
+                    format!(
+                        "Failed to load module graph rooted at '{}': {}",
+                        entry_specifier, err
+                    )
+                    .into(),
+                ));
+            }
+        };
+
+        if entry_module
+            .instantiate_module(try_catch, Self::module_resolve_callback)
+            .is_none()
+        {
+            return Err(AppError::V8ExceptionError(
+                Self::extract_exception_message(
+                    try_catch,
+                    "Failed to instantiate module graph",
+                    entry_specifier,
+                )
+                .into(),
+            ));
+        }
+
+        if entry_module.evaluate(try_catch).is_none() {
+            return Err(AppError::V8ExceptionError(
+                Self::extract_exception_message(
+                    try_catch,
+                    "Failed to evaluate entry module",
+                    entry_specifier,
+                )
+                .into(),
+            ));
+        }
+
+        let namespace = entry_module
+            .get_module_namespace()
+            .to_object(try_catch)
+            .ok_or_else(|| {
+                AppError::V8ExceptionError("Module namespace is not an object".into())
+            })?;
+
+        let default_key = v8::String::new(try_catch, "default").unwrap();
+        let entry_fn = namespace
+            .get(try_catch, default_key.into())
+            .and_then(|value| v8::Local::<v8::Function>::try_from(value).ok())
+            .ok_or_else(|| {
+                AppError::V8ExceptionError("Module has no callable default export".into())
+            })?;
+
+        let params_v8 = match v8::String::new(try_catch, params.unwrap_or_default()) {
+            Some(s) => s.into(),
+            None => v8::undefined(try_catch).into(),
+        };
+
+        let undefined = v8::undefined(try_catch).into();
+        let result = entry_fn.call(try_catch, undefined, &[params_v8]);
+        if try_catch.has_caught() {
+            return Err(AppError::V8ExceptionError(Self::build_js_error(
+                try_catch,
+                "Error calling module default export",
+                entry_specifier,
+            )));
+        }
+
+        Ok(result
+            .expect("Function call did not return a value")
+            .to_rust_string_lossy(try_catch))
+    }
+
+    /// Loads `specifier` and every module it (transitively) imports, compiling each one with
+    /// `v8::script_compiler::compile_module` and registering it in `state` before returning. All
+    /// of this is done eagerly, up front, so `module_resolve_callback` - which V8 calls
+    /// synchronously from `instantiate_module` and so can't itself load anything fallibly - only
+    /// ever has to look an already-compiled module up by specifier.
+    ///
+    /// `specifier` is recorded in `state.in_progress` before recursing into its dependencies, so a
+    /// circular import (A imports B, B imports A - an entirely ordinary ES module pattern) doesn't
+    /// recurse forever: the dependency loop below skips any specifier already in progress, trusting
+    /// that its outer call further up the stack will finish compiling and registering it.
+    fn load_module_graph<'b>(
+        scope: &mut v8::TryCatch<'b, v8::HandleScope>,
+        state: &Rc<RefCell<ModuleMapState>>,
+        specifier: &str,
+    ) -> Result<v8::Local<'b, v8::Module>, String> {
+        if let Some(existing) = state.borrow().modules.get(specifier) {
+            return Ok(v8::Local::new(scope, existing));
+        }
+
+        state.borrow_mut().in_progress.insert(specifier.to_string());
+
+        let source_text = state
+            .borrow()
+            .loader
+            .load(specifier)
+            .map_err(|err| err.to_string())?;
+
+        let dependency_specifiers: Vec<String> =
+            crate::module_loader::extract_import_specifiers(&source_text)
+                .into_iter()
+                .map(|dep| {
+                    state
+                        .borrow()
+                        .loader
+                        .resolve(&dep, specifier)
+                        .map_err(|err| err.to_string())
+                })
+                .collect::<Result<_, _>>()?;
+
+        for dependency in &dependency_specifiers {
+            let already_loading = {
+                let state_ref = state.borrow();
+                state_ref.modules.contains_key(dependency) || state_ref.in_progress.contains(dependency)
+            };
+            if already_loading {
+                continue;
+            }
+            Self::load_module_graph(scope, state, dependency)?;
+        }
+
+        let source_str = v8::String::new(scope, &source_text)
+            .ok_or_else(|| "Failed to create module source string".to_string())?;
+        let name_str = v8::String::new(scope, specifier)
+            .ok_or_else(|| "Failed to create module name string".to_string())?;
+        let origin = v8::ScriptOrigin::new(
+            scope,
+            name_str.into(),
+            0,
+            0,
+            false,
+            0,
+            None,
+            false,
+            false,
+            true,
+            None,
+        );
+        let v8_source = v8::script_compiler::Source::new(source_str, Some(&origin));
+
+        let module = v8::script_compiler::compile_module(scope, v8_source)
+            .ok_or_else(|| format!("Failed to compile module '{}'", specifier))?;
+
+        let identity_hash = module.get_identity_hash();
+        let global = v8::Global::new(scope, module);
+
+        let mut state_mut = state.borrow_mut();
+        state_mut.modules.insert(specifier.to_string(), global);
+        state_mut.specifiers.insert(identity_hash, specifier.to_string());
+        state_mut.in_progress.remove(specifier);
+        drop(state_mut);
+
+        Ok(module)
+    }
+
+    /// V8's `instantiate_module` resolve callback. Every module it could be asked for was already
+    /// compiled and registered by `load_module_graph`'s eager pre-pass, so this is a pure lookup:
+    /// find which specifier `referrer` was registered under, resolve `specifier` relative to it
+    /// through the same `ModuleLoader`, and return the already-compiled module for the result.
+    fn module_resolve_callback<'b>(
+        context: v8::Local<'b, v8::Context>,
+        specifier: v8::Local<'b, v8::String>,
+        _import_assertions: v8::Local<'b, v8::FixedArray>,
+        referrer: v8::Local<'b, v8::Module>,
+    ) -> Option<v8::Local<'b, v8::Module>> {
+        let scope = &mut unsafe { v8::CallbackScope::new(context) };
+        let state = scope.get_slot::<Rc<RefCell<ModuleMapState>>>()?.clone();
+
+        let referrer_specifier = state
+            .borrow()
+            .specifiers
+            .get(&referrer.get_identity_hash())?
+            .clone();
+
+        let specifier_str = specifier.to_rust_string_lossy(scope);
+        let resolved = state
+            .borrow()
+            .loader
+            .resolve(&specifier_str, &referrer_specifier)
+            .ok()?;
+
+        let global = state.borrow().modules.get(&resolved)?.clone();
+        Some(v8::Local::new(scope, &global))
+    }
+}
+
+/// Per-render state backing `Ssr::render_module`'s module graph - stashed as an isolate slot
+/// (`HandleScope::set_slot`/`get_slot`) because `module_resolve_callback` is a plain function
+/// pointer V8 calls directly, with no way to capture Rust closure state the way
+/// `inject_logger`/`install_ops` do via `v8::External`.
+struct ModuleMapState {
+    loader: Arc<dyn ModuleLoader>,
+    modules: HashMap<String, v8::Global<v8::Module>>,
+    specifiers: HashMap<i32, String>,
+    /// Specifiers currently partway through `load_module_graph`'s recursion, i.e. load started but
+    /// the module isn't compiled (and so isn't in `modules`) yet. A specifier re-encountered while
+    /// still in this set is a circular import - an entirely ordinary ES module pattern - and must
+    /// short-circuit the recursion rather than loading it again.
+    in_progress: HashSet<String>,
+}
+
+/// Inverse of `bundle_common`'s `base64_encode` - decodes a standard (RFC 4648) base64 string
+/// back to bytes. Kept local and dependency-free, matching that encoder's existing precedent,
+/// since this is the only place in the crate that needs to go the other direction.
+fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut lookup = [None; 256];
+    for (value, &byte) in ALPHABET.iter().enumerate() {
+        lookup[byte as usize] = Some(value as u8);
+    }
+
+    let digits: Vec<u8> = encoded
+        .bytes()
+        .filter(|&b| b != b'=')
+        .map(|b| lookup[b as usize])
+        .collect::<Option<Vec<u8>>>()?;
+
+    let mut out = Vec::with_capacity(digits.len() * 3 / 4);
+
+    for chunk in digits.chunks(4) {
+        out.push((chunk[0] << 2) | (chunk.get(1).copied().unwrap_or(0) >> 4));
+        if chunk.len() > 2 {
+            out.push((chunk[1] << 4) | (chunk[2] >> 2));
+        }
+        if chunk.len() > 3 {
+            out.push((chunk[2] << 6) | chunk[3]);
+        }
+    }
+
+    Some(out)
 }
 
 pub fn run_ssr(js_string: String, hard_timeout: u64) -> Result<String, AppError> {
@@ -328,6 +1198,24 @@ pub fn run_ssr(js_string: String, hard_timeout: u64) -> Result<String, AppError>
     }
 }
 
+/// Same as [`run_ssr`], but resolves a `Promise`-returning entry function - see
+/// [`Ssr::render_to_string_async`].
+pub fn run_ssr_async(js_string: String, hard_timeout: u64) -> Result<String, AppError> {
+    if hard_timeout > 0 {
+        timeout::run_thread_with_timeout(
+            || {
+                let js = Ssr::new(js_string, "SSR");
+                js.render_to_string_async(None)
+            },
+            Duration::from_millis(hard_timeout),
+        )
+    } else {
+        // Call inline, no timeout
+        let js = Ssr::new(js_string, "SSR");
+        js.render_to_string_async(None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -361,7 +1249,13 @@ mod tests {
             js,
             Ssr {
                 source: r##"var SSR = {x: () => "<html></html>"};"##.to_string(),
-                entry_point: "SSR"
+                entry_point: "SSR",
+                snapshot: None,
+                ops: None,
+                cpu_soft_budget: None,
+                cpu_hard_budget: None,
+                request_id: None,
+                console_capture: None,
             }
         )
     }
@@ -380,10 +1274,179 @@ mod tests {
         );
         let result = js.render_to_string(None);
 
+        match result {
+            Err(AppError::V8ExceptionError(err)) => {
+                assert_eq!(
+                    err.message,
+                    "Error calling function 'x': Error: custom_error_text\nStack: Error: custom_error_text\n    at Object.x (<anonymous>:4:31)"
+                );
+                assert_eq!(err.class_name, Some("Error".to_string()));
+                assert_eq!(err.frames.len(), 1);
+                assert_eq!(err.frames[0].function_name.as_deref(), Some("Object.x"));
+                assert_eq!(err.frames[0].line, Some(4));
+                assert_eq!(err.frames[0].column, Some(31));
+            }
+            other => panic!("expected a V8ExceptionError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_exception_frame_is_remapped_through_the_source_map() {
+        // Base64 of {"version":3,"sources":["src/App.tsx"],"names":[],"mappings":"CAAA"} - same
+        // fixture `test_remap_stack_trace_reads_embedded_data_uri_map` uses for the string path.
+        // `err.frames[].line/column` must land on the same remapped position as `err.message`'s
+        // embedded stack string does, instead of still pointing at the bundled source.
+        // Everything on one line, including the throw, so the frame's line number (1) falls
+        // within the single mapped line `mappings: "CAAA"` covers - a later line would fall
+        // outside the map's range and skip remapping entirely. The trailing newline keeps the
+        // `;SSR` that `render` appends off the sourceMappingURL comment's line.
+        let js = Ssr::new(
+            r#"var SSR = { x: () => { throw new Error('custom_error_text') } };
+//# sourceMappingURL=data:application/json;base64,eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbInNyYy9BcHAudHN4Il0sIm5hbWVzIjpbXSwibWFwcGluZ3MiOiJDQUFBIn0=
+"#
+                .to_string(),
+            "SSR",
+        );
+        let result = js.render_to_string(None);
+
+        match result {
+            Err(AppError::V8ExceptionError(err)) => {
+                assert_eq!(err.frames.len(), 1);
+                assert_eq!(err.frames[0].file_name.as_deref(), Some("src/App.tsx"));
+                assert_eq!(err.frames[0].line, Some(1));
+                assert_eq!(err.frames[0].column, Some(1));
+            }
+            other => panic!("expected a V8ExceptionError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_exception_raw_stack_is_gated_behind_env_var() {
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: () => {
+                        throw new Error('custom_error_text')
+                    }
+                };"##
+                .to_string(),
+            "SSR",
+        );
+
+        let result = js.render_to_string(None);
+        match result {
+            Err(AppError::V8ExceptionError(err)) => assert_eq!(err.raw_stack, None),
+            other => panic!("expected a V8ExceptionError, got {:?}", other),
+        }
+
+        std::env::set_var("MOUNTAINEER_SSR_RAW_STACK", "1");
+        let result = js.render_to_string(None);
+        std::env::remove_var("MOUNTAINEER_SSR_RAW_STACK");
+
+        match result {
+            Err(AppError::V8ExceptionError(err)) => {
+                let raw_stack = err.raw_stack.expect("expected a raw stack to be captured");
+                assert!(raw_stack.contains("at Object.x"));
+            }
+            other => panic!("expected a V8ExceptionError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_cpu_hard_budget_terminates_a_runaway_render() {
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: () => {
+                        while (true) {}
+                    }
+                };"##
+                .to_string(),
+            "SSR",
+        )
+        .with_cpu_budget(Duration::from_millis(20), Duration::from_millis(50));
+
+        let result = js.render_to_string(None);
+
+        assert_eq!(
+            result,
+            Err(AppError::HardTimeoutError(
+                "Error calling function 'x' (CPU hard budget exceeded)".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn check_cpu_hard_budget_terminates_a_promise_that_never_settles() {
+        // `x` returns immediately with a promise nothing ever resolves. Before the fix,
+        // `await_promise`'s checkpoint loop would keep polling `promise.state()` forever once the
+        // isolate was terminated, since termination doesn't make a pending promise settle on its
+        // own - this asserts it instead bails out with a hard-timeout error.
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: () => new Promise(() => {}),
+                };"##
+                .to_string(),
+            "SSR",
+        )
+        .with_cpu_budget(Duration::from_millis(20), Duration::from_millis(50));
+
+        let result = js.render_to_string_async(None);
+
         assert_eq!(
             result,
-            Err(AppError::V8ExceptionError("Error calling function 'x': Error: custom_error_text\nStack: Error: custom_error_text\n    at Object.x (<anonymous>:4:31)".into()))
+            Err(AppError::HardTimeoutError(
+                "Error calling function 'x' (CPU hard budget exceeded)".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_base64_decode_round_trips_with_and_without_padding() {
+        assert_eq!(base64_decode("aGVsbG8=").unwrap(), b"hello".to_vec());
+        assert_eq!(base64_decode("aGVsbG8").unwrap(), b"hello".to_vec());
+    }
+
+    #[test]
+    fn test_remap_stack_trace_reads_sibling_map_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let map_path = temp_dir.path().join("bundle.js.map");
+        std::fs::write(
+            &map_path,
+            r#"{"version":3,"sources":["src/App.tsx"],"names":[],"mappings":"CAAA"}"#,
         )
+        .unwrap();
+
+        let source = format!(
+            "var x = 1;\n//# sourceMappingURL={}",
+            map_path.to_string_lossy()
+        );
+        let raw_trace = "Error: boom\n    at Object.x (<anonymous>:1:2)";
+
+        let remapped = Ssr::remap_stack_trace(raw_trace, &source);
+
+        assert_eq!(remapped, "Error: boom\n    at Object.x (src/App.tsx:1:1)");
+    }
+
+    #[test]
+    fn test_remap_stack_trace_reads_embedded_data_uri_map() {
+        // Base64 of {"version":3,"sources":["src/App.tsx"],"names":[],"mappings":"CAAA"}
+        let source = "var x = 1;\n//# sourceMappingURL=data:application/json;base64,eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbInNyYy9BcHAudHN4Il0sIm5hbWVzIjpbXSwibWFwcGluZ3MiOiJDQUFBIn0=";
+        let raw_trace = "Error: boom\n    at Object.x (<anonymous>:1:2)";
+
+        let remapped = Ssr::remap_stack_trace(raw_trace, source);
+
+        assert_eq!(remapped, "Error: boom\n    at Object.x (src/App.tsx:1:1)");
+    }
+
+    #[test]
+    fn test_remap_stack_trace_leaves_frame_untouched_without_source_map() {
+        let raw_trace = "Error: boom\n    at Object.x (<anonymous>:1:2)";
+
+        let remapped = Ssr::remap_stack_trace(raw_trace, "var x = 1;");
+
+        assert_eq!(remapped, raw_trace);
     }
 
     #[test]
@@ -419,6 +1482,12 @@ mod tests {
             "SSR",
             None,
             stdout.clone(),
+            None,
+            None,
+            false,
+            None,
+            None,
+            None,
         );
 
         let result_vector = stdout.lock().unwrap();
@@ -430,6 +1499,234 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_log_to_stdout_tags_lines_with_request_id() {
+        let stdout = Arc::new(Mutex::new(Vec::new()));
+
+        Ssr::init_platform();
+        let result = Ssr::render(
+            r##"
+                var SSR = {
+                    x: () => {
+                        console.warn('something odd');
+                        return "<html></html>"
+                    }
+                };"##
+                .to_string(),
+            "SSR",
+            None,
+            stdout.clone(),
+            None,
+            None,
+            false,
+            None,
+            None,
+            Some("req-123".to_string()),
+        );
+
+        let result_vector = stdout.lock().unwrap();
+
+        assert_eq!(result, Ok("<html></html>".to_string()));
+        assert_eq!(
+            String::from_utf8_lossy(&*result_vector),
+            "ssr console [req-123][warn]: something odd\n"
+        );
+    }
+
+    #[test]
+    fn test_console_capture_retrieves_only_this_renders_output() {
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: () => {
+                        console.log('captured line');
+                        return "<html></html>"
+                    }
+                };"##
+                .to_string(),
+            "SSR",
+        )
+        .with_console_capture();
+
+        let result = js.render_to_string(None);
+
+        assert_eq!(result, Ok("<html></html>".to_string()));
+        assert_eq!(
+            js.take_console_output(),
+            Some("ssr console [log]: captured line\n".to_string())
+        );
+        // Draining resets the buffer.
+        assert_eq!(js.take_console_output(), Some(String::new()));
+    }
+
+    #[test]
+    fn test_with_ops_exposes_globalthis_ops_to_js() {
+        let mut registry = OpRegistry::new();
+        registry.register("double", |args| {
+            let n = args[0].as_i64().unwrap_or(0);
+            Ok(serde_json::json!(n * 2))
+        });
+
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: () => `result: ${globalThis.__ops.double(21)}`
+                };"##
+                .to_string(),
+            "SSR",
+        )
+        .with_ops(registry);
+        let result = js.render_to_string(None);
+
+        assert_eq!(result, Ok("result: 42".to_string()));
+    }
+
+    #[test]
+    fn test_with_ops_propagates_op_error_as_js_exception() {
+        let mut registry = OpRegistry::new();
+        registry.register("always_fails", |_args| {
+            Err(AppError::V8ExceptionError("op failed".into()))
+        });
+
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: () => globalThis.__ops.always_fails()
+                };"##
+                .to_string(),
+            "SSR",
+        )
+        .with_ops(registry);
+        let result = js.render_to_string(None);
+
+        assert!(
+            matches!(result, Err(AppError::V8ExceptionError(ref err)) if err.message.contains("op failed"))
+        );
+    }
+
+    #[test]
+    fn test_render_with_snapshot_reuses_precompiled_context() {
+        let snapshot =
+            crate::snapshot::build_snapshot("function greet() { return 'hello from snapshot'; }");
+
+        let js = Ssr::with_snapshot(
+            r##"var SSR = { x: () => greet() };"##.to_string(),
+            "SSR",
+            Arc::from(snapshot),
+        );
+        let result = js.render_to_string(None);
+
+        assert_eq!(result, Ok("hello from snapshot".to_string()));
+    }
+
+    #[test]
+    fn test_render_to_string_async_awaits_a_resolved_promise() {
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: async () => {
+                        await Promise.resolve();
+                        return "<html></html>";
+                    }
+                };"##
+                .to_string(),
+            "SSR",
+        );
+        let result = js.render_to_string_async(None);
+
+        assert_eq!(result, Ok("<html></html>".to_string()));
+    }
+
+    #[test]
+    fn test_render_to_string_async_surfaces_a_rejected_promise() {
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: async () => {
+                        throw new Error('async_error_text');
+                    }
+                };"##
+                .to_string(),
+            "SSR",
+        );
+        let result = js.render_to_string_async(None);
+
+        assert!(matches!(
+            result,
+            Err(AppError::V8ExceptionError(ref err)) if err.message.contains("async_error_text")
+        ));
+    }
+
+    #[test]
+    fn test_render_to_string_without_async_mode_stringifies_a_pending_promise() {
+        // A promise-returning function rendered through the synchronous path never gets
+        // resolved - it's coerced straight to its (useless) string form, same as any other
+        // object would be. This documents that `render_to_string_async` is required for
+        // promise-returning entry points, not a bug in the synchronous path.
+        let js = Ssr::new(
+            r##"
+                var SSR = {
+                    x: () => Promise.resolve("<html></html>")
+                };"##
+                .to_string(),
+            "SSR",
+        );
+        let result = js.render_to_string(None);
+
+        assert_eq!(result, Ok("[object Promise]".to_string()));
+    }
+
+    #[test]
+    fn test_render_module_resolves_a_static_import_graph() {
+        let mut loader = crate::module_loader::InMemoryModuleLoader::new();
+        loader.add(
+            "entry.js",
+            r#"import { greet } from "./helper.js"; export default () => greet("world");"#,
+        );
+        loader.add(
+            "helper.js",
+            r#"export function greet(name) { return `hello ${name}`; }"#,
+        );
+
+        let result = Ssr::render_module(Arc::new(loader), "entry.js", None);
+
+        assert_eq!(result, Ok("hello world".to_string()));
+    }
+
+    #[test]
+    fn test_render_module_surfaces_missing_dependency_as_an_error() {
+        let mut loader = crate::module_loader::InMemoryModuleLoader::new();
+        loader.add(
+            "entry.js",
+            r#"import { greet } from "./missing.js"; export default () => greet();"#,
+        );
+
+        let result = Ssr::render_module(Arc::new(loader), "entry.js", None);
+
+        assert!(matches!(result, Err(AppError::V8ExceptionError(_))));
+    }
+
+    #[test]
+    fn test_render_module_handles_a_circular_import_without_overflowing_the_stack() {
+        // a.js and b.js import each other - an entirely ordinary ES module cycle. Before the fix,
+        // load_module_graph's eager pre-pass recursed forever on this (a loads b, b loads a, ...)
+        // since nothing was registered until after a module's *own* dependencies all finished
+        // loading.
+        let mut loader = crate::module_loader::InMemoryModuleLoader::new();
+        loader.add(
+            "a.js",
+            r#"import { b } from "./b.js"; export function a() { return "a"; } export default () => a() + b();"#,
+        );
+        loader.add(
+            "b.js",
+            r#"import { a } from "./a.js"; export function b() { return "b"; }"#,
+        );
+
+        let result = Ssr::render_module(Arc::new(loader), "a.js", None);
+
+        assert_eq!(result, Ok("ab".to_string()));
+    }
+
     #[test]
     fn test_timezone_succeeds() {
         // More context:
@@ -0,0 +1,219 @@
+//! Cross-platform CPU time sampling, used by [`crate::bundle_prod`] (via
+//! [`current_thread_cpu_usage`]) to distinguish genuinely CPU-bound build phases (minification)
+//! from I/O stalls, and by [`crate::cpu_watchdog`] (via [`ThreadCpuHandle`]) to sample a render's
+//! worker thread from a separate monitoring thread. This mirrors the measurement primitives
+//! behind `filzl-daemons`'s worker-thread accounting, duplicated here rather than taken as a
+//! dependency since this crate has no workspace manifest tying it to that sibling plugin crate.
+
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum ThreadCpuError {
+    SyscallFailed(String),
+}
+
+impl std::fmt::Display for ThreadCpuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ThreadCpuError::SyscallFailed(msg) => write!(f, "thread CPU syscall failed: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ThreadCpuError {}
+
+#[cfg(target_os = "linux")]
+pub fn current_thread_cpu_usage() -> Result<Duration, ThreadCpuError> {
+    thread_clock_cpu_time(libc::CLOCK_THREAD_CPUTIME_ID)
+}
+
+#[cfg(target_os = "linux")]
+fn thread_clock_cpu_time(clock_id: libc::clockid_t) -> Result<Duration, ThreadCpuError> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    let result = unsafe { libc::clock_gettime(clock_id, &mut ts) };
+    if result != 0 {
+        return Err(ThreadCpuError::SyscallFailed(format!(
+            "clock_gettime failed for clock {clock_id}"
+        )));
+    }
+    Ok(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+}
+
+/// A handle to a specific thread's CPU clock, captured on that thread (via [`Self::current`]) and
+/// safe to read (via [`Self::cpu_time`]) from any other thread - unlike [`current_thread_cpu_usage`],
+/// which only ever reads the *calling* thread. Used by [`crate::cpu_watchdog::CpuWatchdog`] to
+/// sample a render's worker thread from its own monitoring thread.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy)]
+pub struct ThreadCpuHandle(libc::pthread_t);
+
+#[cfg(target_os = "linux")]
+unsafe impl Send for ThreadCpuHandle {}
+#[cfg(target_os = "linux")]
+unsafe impl Sync for ThreadCpuHandle {}
+
+#[cfg(target_os = "linux")]
+impl ThreadCpuHandle {
+    /// Captures a handle to the calling thread, to be read later from a different thread.
+    pub fn current() -> Self {
+        ThreadCpuHandle(unsafe { libc::pthread_self() })
+    }
+
+    /// Reads the CPU time this handle's thread has consumed so far. Safe to call from any thread.
+    pub fn cpu_time(&self) -> Result<Duration, ThreadCpuError> {
+        let mut clock_id: libc::clockid_t = 0;
+        let result = unsafe { libc::pthread_getcpuclockid(self.0, &mut clock_id) };
+        if result != 0 {
+            return Err(ThreadCpuError::SyscallFailed(
+                "pthread_getcpuclockid failed".to_string(),
+            ));
+        }
+        thread_clock_cpu_time(clock_id)
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn current_thread_cpu_usage() -> Result<Duration, ThreadCpuError> {
+    let thread_port = unsafe { mach_thread_self() };
+    let result = mach_thread_cpu_time(thread_port);
+    unsafe {
+        mach_port_deallocate(libc::mach_task_self(), thread_port);
+    }
+    result
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct ThreadBasicInfo {
+    user_time: libc::time_value_t,
+    system_time: libc::time_value_t,
+    cpu_usage: libc::integer_t,
+    policy: libc::integer_t,
+    run_state: libc::integer_t,
+    flags: libc::integer_t,
+    suspend_count: libc::integer_t,
+    sleep_time: libc::integer_t,
+}
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn mach_thread_self() -> libc::mach_port_t;
+    fn pthread_mach_thread_np(thread: libc::pthread_t) -> libc::mach_port_t;
+    fn thread_info(
+        target_thread: libc::mach_port_t,
+        flavor: libc::thread_flavor_t,
+        thread_info_out: *mut libc::integer_t,
+        thread_info_out_count: *mut libc::mach_msg_type_number_t,
+    ) -> libc::kern_return_t;
+    fn mach_port_deallocate(task: libc::mach_port_t, name: libc::mach_port_t)
+        -> libc::kern_return_t;
+}
+
+/// Reads `user_time + system_time` for the mach thread identified by `thread_port`, shared by
+/// [`current_thread_cpu_usage`] and [`ThreadCpuHandle::cpu_time`].
+#[cfg(target_os = "macos")]
+fn mach_thread_cpu_time(thread_port: libc::mach_port_t) -> Result<Duration, ThreadCpuError> {
+    unsafe {
+        let mut info = std::mem::zeroed::<ThreadBasicInfo>();
+        let mut count = std::mem::size_of::<ThreadBasicInfo>() as libc::mach_msg_type_number_t
+            / std::mem::size_of::<libc::integer_t>() as libc::mach_msg_type_number_t;
+
+        let result = thread_info(
+            thread_port,
+            libc::THREAD_BASIC_INFO as libc::thread_flavor_t,
+            &mut info as *mut _ as *mut libc::integer_t,
+            &mut count,
+        );
+
+        if result != libc::KERN_SUCCESS {
+            return Err(ThreadCpuError::SyscallFailed(format!(
+                "thread_info returned {result}"
+            )));
+        }
+
+        let user = Duration::new(
+            info.user_time.seconds as u64,
+            (info.user_time.microseconds as u32) * 1_000,
+        );
+        let system = Duration::new(
+            info.system_time.seconds as u64,
+            (info.system_time.microseconds as u32) * 1_000,
+        );
+        Ok(user + system)
+    }
+}
+
+/// Same role as the Linux `ThreadCpuHandle` above, backed by the thread's mach port instead of a
+/// `pthread_getcpuclockid`-derived clock id - macOS doesn't expose the latter.
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy)]
+pub struct ThreadCpuHandle(libc::mach_port_t);
+
+#[cfg(target_os = "macos")]
+unsafe impl Send for ThreadCpuHandle {}
+#[cfg(target_os = "macos")]
+unsafe impl Sync for ThreadCpuHandle {}
+
+#[cfg(target_os = "macos")]
+impl ThreadCpuHandle {
+    pub fn current() -> Self {
+        ThreadCpuHandle(unsafe { pthread_mach_thread_np(libc::pthread_self()) })
+    }
+
+    pub fn cpu_time(&self) -> Result<Duration, ThreadCpuError> {
+        mach_thread_cpu_time(self.0)
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn current_thread_cpu_usage() -> Result<Duration, ThreadCpuError> {
+    use winapi::shared::minwindef::FILETIME;
+    use winapi::um::processthreadsapi::{GetCurrentThread, GetThreadTimes};
+
+    unsafe {
+        let mut creation_time = std::mem::zeroed::<FILETIME>();
+        let mut exit_time = std::mem::zeroed::<FILETIME>();
+        let mut kernel_time = std::mem::zeroed::<FILETIME>();
+        let mut user_time = std::mem::zeroed::<FILETIME>();
+
+        let result = GetThreadTimes(
+            GetCurrentThread(),
+            &mut creation_time,
+            &mut exit_time,
+            &mut kernel_time,
+            &mut user_time,
+        );
+        if result == 0 {
+            return Err(ThreadCpuError::SyscallFailed(
+                "GetThreadTimes failed".to_string(),
+            ));
+        }
+
+        let to_100ns = |ft: &FILETIME| ((ft.dwHighDateTime as u64) << 32) | ft.dwLowDateTime as u64;
+        let total_100ns = to_100ns(&kernel_time) + to_100ns(&user_time);
+        Ok(Duration::from_nanos(total_100ns * 100))
+    }
+}
+
+/// `GetThreadTimes` needs a handle valid on the thread that calls it, so unlike Linux/macOS there's
+/// no cheap way to sample an arbitrary *other* thread's CPU time here yet - `cpu_time` always
+/// errors, which `CpuWatchdog` treats as "never trips" rather than panicking.
+#[cfg(target_os = "windows")]
+#[derive(Clone, Copy)]
+pub struct ThreadCpuHandle;
+
+#[cfg(target_os = "windows")]
+impl ThreadCpuHandle {
+    pub fn current() -> Self {
+        ThreadCpuHandle
+    }
+
+    pub fn cpu_time(&self) -> Result<Duration, ThreadCpuError> {
+        Err(ThreadCpuError::SyscallFailed(
+            "cross-thread CPU time sampling is not supported on Windows yet".to_string(),
+        ))
+    }
+}
@@ -1,10 +1,33 @@
 use std::result::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
 use crate::errors::AppError;
 
+/// A cloneable cancellation flag handed to the closure passed to
+/// [`run_thread_with_soft_timeout`]. The closure is expected to poll
+/// [`CancellationToken::is_cancelled`] at its own loop boundaries (rather than being forcibly
+/// killed) and return `Err(AppError::SoftTimeoutError)` once it observes the flag.
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self(Arc::new(AtomicBool::new(false)))
+    }
+
+    fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
 #[cfg(unix)]
 mod platform {
     use std::os::unix::thread::JoinHandleExt;
@@ -65,6 +88,59 @@ where
     }
 }
 
+/// Same shape as [`run_thread_with_timeout`], but gives `func` a chance to unwind on its own
+/// before resorting to the OS-level cancel that function uses unconditionally.
+///
+/// Once `soft_timeout` elapses, `func`'s [`CancellationToken`] is flagged and we wait up to an
+/// additional `grace_period` for `func` to notice it (at its own loop boundary) and return
+/// `Err(AppError::SoftTimeoutError)` on its own. Only if `func` still hasn't returned once the
+/// grace period also expires do we fall back to [`platform::cancel_thread`] - which, as that
+/// module documents, can leak whatever `func` had allocated, so this path should stay the rare
+/// exception rather than the common case. Closures that can't poll a token at all (e.g. a single
+/// opaque call into V8) should keep using [`run_thread_with_timeout`] instead.
+pub fn run_thread_with_soft_timeout<F, R>(
+    func: F,
+    soft_timeout: Duration,
+    grace_period: Duration,
+) -> Result<R, AppError>
+where
+    F: FnOnce(CancellationToken) -> Result<R, AppError> + Send + 'static,
+    R: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let token = CancellationToken::new();
+    let worker_token = token.clone();
+
+    let handle = thread::spawn(move || {
+        let result = func(worker_token);
+        tx.send(result).expect("Failed to send result");
+    });
+
+    if let Ok(result) = rx.recv_timeout(soft_timeout) {
+        let _ = handle.join();
+        return result;
+    }
+
+    // Soft timeout reached - ask the closure to unwind on its own before resorting to a hard
+    // cancel.
+    token.cancel();
+
+    match rx.recv_timeout(grace_period) {
+        Ok(result) => {
+            let _ = handle.join();
+            result
+        }
+        Err(_) => {
+            unsafe {
+                platform::cancel_thread(handle);
+            }
+            Err(AppError::HardTimeoutError(
+                "Function execution timed out".into(),
+            ))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +215,77 @@ mod tests {
         assert_eq!(result, Ok("returns instantly"));
         assert!(start.elapsed() < Duration::from_millis(500));
     }
+
+    #[test]
+    fn test_run_thread_with_soft_timeout_unwinds_cooperatively() {
+        let start = std::time::Instant::now();
+        let result = run_thread_with_soft_timeout(
+            |token| {
+                let mut largest_prime = 0;
+                for n in 2..=100_000_000 {
+                    // Outrageously large amount of processing - for all intents will never
+                    // complete, so the loop boundary check below is what actually ends it.
+                    if n % 1_000 == 0 && token.is_cancelled() {
+                        return Err(AppError::SoftTimeoutError(
+                            "Cancelled before completion".into(),
+                        ));
+                    }
+                    if is_prime(n) {
+                        largest_prime = n;
+                    }
+                }
+                Ok(largest_prime)
+            },
+            Duration::from_millis(200),
+            Duration::from_secs(5),
+        );
+
+        assert_eq!(
+            result,
+            Err(AppError::SoftTimeoutError(
+                "Cancelled before completion".into()
+            ))
+        );
+        // The closure should unwind well within the grace period, not burn all of it.
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_run_thread_with_soft_timeout_falls_back_to_hard_cancel() {
+        let start = std::time::Instant::now();
+        let result = run_thread_with_soft_timeout(
+            |_token| {
+                // Never checks the token, so the soft path can't save it - only the hard
+                // fallback after the grace period will end this.
+                let mut largest_prime = 0;
+                for n in 2..=100_000_000 {
+                    if is_prime(n) {
+                        largest_prime = n;
+                    }
+                }
+                Ok(largest_prime)
+            },
+            Duration::from_millis(200),
+            Duration::from_millis(200),
+        );
+
+        assert_eq!(
+            result,
+            Err(AppError::HardTimeoutError(
+                "Function execution timed out".into()
+            ))
+        );
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_run_thread_with_soft_timeout_valid() {
+        let result = run_thread_with_soft_timeout(
+            |_token| Ok("returns instantly"),
+            Duration::from_millis(500),
+            Duration::from_millis(500),
+        );
+
+        assert_eq!(result, Ok("returns instantly"));
+    }
 }
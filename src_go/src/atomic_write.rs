@@ -0,0 +1,65 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Write `contents` to `dest` without ever exposing a reader to a partially-written file.
+///
+/// The bytes are written to a sibling temp file in the same directory as `dest`, fsync'd to
+/// guarantee they have hit disk, then moved into place with a single `rename` syscall. Since
+/// `rename` is atomic on POSIX filesystems (and on Windows when the destination doesn't already
+/// have open handles blocking replacement), a concurrent reader - e.g. a live SSR server polling
+/// `ssr.js.out` - always observes either the complete previous file or the complete new one,
+/// never a truncated write in between. This is the same pattern Deno uses for all of its
+/// generated-file writes.
+pub fn write_atomic(dest: &Path, contents: &[u8]) -> io::Result<()> {
+    let parent = dest.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = dest
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Destination has no filename"))?;
+
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = parent.join(temp_name);
+
+    {
+        let mut temp_file = File::create(&temp_path)?;
+        temp_file.write_all(contents)?;
+        temp_file.sync_all()?;
+    }
+
+    fs::rename(&temp_path, dest)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_atomic_creates_file() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("ssr.js.out");
+
+        write_atomic(&dest, b"first").unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"first");
+
+        write_atomic(&dest, b"second").unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"second");
+    }
+
+    #[test]
+    fn test_write_atomic_leaves_no_temp_files() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("ssr.js.out");
+
+        write_atomic(&dest, b"contents").unwrap();
+
+        let remaining: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(remaining, vec![std::ffi::OsString::from("ssr.js.out")]);
+    }
+}
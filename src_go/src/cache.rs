@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::ffi::c_int;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use log::debug;
+use rustc_hash::FxHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::atomic_write;
+use crate::rebuild_context;
+
+/// Everything that feeds into whether a context's last bundle is still valid. Mirrors the inputs
+/// Deno hashes into its module lockfile: the source files themselves plus the build flags that
+/// change what gets emitted for the same files.
+#[derive(Debug, Clone)]
+pub struct ContextCacheInputs {
+    pub input_files: Vec<PathBuf>,
+    pub environment: String,
+    pub is_server: bool,
+    pub minify: bool,
+    /// Contents of the resolved node_modules lockfile (e.g. package-lock.json/yarn.lock), if any.
+    pub lockfile_contents: Option<String>,
+    pub output_path: PathBuf,
+}
+
+impl ContextCacheInputs {
+    fn content_hash(&self) -> Result<u64, String> {
+        let mut hasher = FxHasher::default();
+
+        for path in &self.input_files {
+            let contents = fs::read(path)
+                .map_err(|e| format!("Failed to read input file {}: {:?}", path.display(), e))?;
+            contents.hash(&mut hasher);
+        }
+
+        self.environment.hash(&mut hasher);
+        self.is_server.hash(&mut hasher);
+        self.minify.hash(&mut hasher);
+        self.lockfile_contents.hash(&mut hasher);
+
+        Ok(hasher.finish())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct CacheManifest {
+    // context_id -> (content hash, output path at the time of caching)
+    entries: HashMap<c_int, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    hash: u64,
+    output_path: PathBuf,
+}
+
+fn manifest_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("manifest.json")
+}
+
+fn load_manifest(cache_dir: &Path) -> CacheManifest {
+    let path = manifest_path(cache_dir);
+    fs::read_to_string(&path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(cache_dir: &Path, manifest: &CacheManifest) -> Result<(), String> {
+    fs::create_dir_all(cache_dir)
+        .map_err(|e| format!("Failed to create cache directory: {:?}", e))?;
+    let serialized = serde_json::to_string(manifest)
+        .map_err(|e| format!("Failed to serialize cache manifest: {:?}", e))?;
+    atomic_write::write_atomic(&manifest_path(cache_dir), serialized.as_bytes())
+        .map_err(|e| format!("Failed to write cache manifest: {:?}", e))
+}
+
+/// Rebuild the context at `context_ptr` unless its content hash matches the last recorded hash in `cache_dir` and its
+/// output file still exists on disk, in which case the rebuild is skipped entirely. Returns
+/// whether a rebuild actually ran (`false` means it was served from cache).
+pub fn rebuild_context_cached(
+    context_ptr: c_int,
+    inputs: &ContextCacheInputs,
+    cache_dir: &Path,
+    force: bool,
+) -> Result<bool, String> {
+    let mut manifest = load_manifest(cache_dir);
+    let hash = inputs.content_hash()?;
+
+    if !force {
+        if let Some(entry) = manifest.entries.get(&context_ptr) {
+            if entry.hash == hash && entry.output_path.exists() {
+                debug!("Cache hit for context {}, skipping rebuild", context_ptr);
+                return Ok(false);
+            }
+        }
+    }
+
+    rebuild_context(context_ptr)?;
+
+    manifest.entries.insert(
+        context_ptr,
+        CacheEntry {
+            hash,
+            output_path: inputs.output_path.clone(),
+        },
+    );
+    save_manifest(cache_dir, &manifest)?;
+
+    Ok(true)
+}
+
+/// Record a manifest entry as if `id` had just been built, without actually calling
+/// `rebuild_context`. Lets other modules' tests exercise a cache *hit* through
+/// [`rebuild_context_cached`] without needing a real build context.
+#[cfg(test)]
+pub(crate) fn prime_manifest_entry_for_test(
+    cache_dir: &Path,
+    id: c_int,
+    inputs: &ContextCacheInputs,
+) -> Result<(), String> {
+    let mut manifest = load_manifest(cache_dir);
+    let hash = inputs.content_hash()?;
+    manifest.entries.insert(
+        id,
+        CacheEntry {
+            hash,
+            output_path: inputs.output_path.clone(),
+        },
+    );
+    save_manifest(cache_dir, &manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manifest = CacheManifest::default();
+        manifest.entries.insert(
+            1,
+            CacheEntry {
+                hash: 42,
+                output_path: PathBuf::from("/tmp/ssr.js.out"),
+            },
+        );
+
+        save_manifest(dir.path(), &manifest).unwrap();
+        let loaded = load_manifest(dir.path());
+
+        assert_eq!(loaded.entries.get(&1).unwrap().hash, 42);
+    }
+
+    #[test]
+    fn test_content_hash_changes_with_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("entry.js");
+        fs::write(&file_path, b"export default 1;").unwrap();
+
+        let base = ContextCacheInputs {
+            input_files: vec![file_path.clone()],
+            environment: "development".to_string(),
+            is_server: false,
+            minify: false,
+            lockfile_contents: None,
+            output_path: dir.path().join("entry.js.out"),
+        };
+
+        let mut minified = base.clone();
+        minified.minify = true;
+
+        assert_ne!(base.content_hash().unwrap(), minified.content_hash().unwrap());
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use glob::Pattern;
+use ignore::WalkBuilder;
+
+use crate::bundle_all;
+
+/// Include/exclude glob patterns for a directory walk, modeled on Deno's `FilePatterns`. Patterns
+/// are matched against paths relative to `root`.
+#[derive(Debug, Clone)]
+pub struct FilePatterns {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl FilePatterns {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        Self { include, exclude }
+    }
+
+    fn matches(&self, relative_path: &Path) -> bool {
+        let included = self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .filter_map(|pattern| Pattern::new(pattern).ok())
+                .any(|pattern| pattern.matches_path(relative_path));
+
+        if !included {
+            return false;
+        }
+
+        !self
+            .exclude
+            .iter()
+            .filter_map(|pattern| Pattern::new(pattern).ok())
+            .any(|pattern| pattern.matches_path(relative_path))
+    }
+}
+
+/// Walks `root`, honoring `.gitignore`/`.ignore` files the same way `ignore::WalkBuilder` does
+/// for tools like ripgrep and Deno's own `collect_files`, and returns every file whose
+/// root-relative path satisfies `patterns`. Paths are canonicalized and deduplicated so a file
+/// matched by two overlapping include patterns is only returned once.
+pub fn collect_entrypoints(
+    root: &Path,
+    patterns: &FilePatterns,
+) -> Result<Vec<String>, String> {
+    let mut matched: BTreeSet<PathBuf> = BTreeSet::new();
+
+    let walker = WalkBuilder::new(root).git_ignore(true).hidden(false).build();
+
+    for entry in walker {
+        let entry = entry.map_err(|e| format!("Failed to walk {:?}: {:?}", root, e))?;
+
+        if !entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
+            continue;
+        }
+
+        let path = entry.path();
+        let relative_path = path.strip_prefix(root).unwrap_or(path);
+
+        if !patterns.matches(relative_path) {
+            continue;
+        }
+
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| format!("Failed to canonicalize {:?}: {:?}", path, e))?;
+        matched.insert(canonical);
+    }
+
+    Ok(matched
+        .into_iter()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
+
+/// Convenience wrapper that discovers entrypoints under `root` via [`collect_entrypoints`] and
+/// feeds the result straight into [`bundle_all`], so callers no longer have to enumerate every
+/// page/controller path by hand.
+pub fn bundle_all_from_patterns(
+    root: &Path,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    node_modules_path: String,
+    environment: String,
+    minify: bool,
+    outdir: String,
+) -> Result<(), String> {
+    let patterns = FilePatterns::new(include, exclude);
+    let paths = collect_entrypoints(root, &patterns)?;
+    bundle_all(paths, node_modules_path, environment, minify, outdir)
+}
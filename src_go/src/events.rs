@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::os::raw::c_int;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cache::{self, ContextCacheInputs};
+use crate::rebuild_context;
+
+/// Terminal state of a single context's rebuild, carried on [`BuildEvent::Result`].
+#[derive(Debug, Clone)]
+pub enum BuildOutcome {
+    Ok,
+    /// The context's last bundle was still valid per [`ContextCacheInputs::content_hash`], so
+    /// the `RebuildContext` FFI call was skipped entirely.
+    Cached,
+    Error(String),
+    TimedOut,
+}
+
+/// Optional content-hash cache threaded through [`rebuild_contexts_stream`]. When a context has
+/// an entry in `inputs`, its rebuild goes through [`cache::rebuild_context_cached`] instead of
+/// calling `RebuildContext` unconditionally, turning a cold rebuild of an unchanged context into
+/// a near-instant no-op. Contexts absent from `inputs` always rebuild normally.
+#[derive(Debug, Clone)]
+pub struct RebuildCache {
+    pub cache_dir: PathBuf,
+    pub inputs: HashMap<c_int, ContextCacheInputs>,
+    pub force: bool,
+}
+
+/// A structured build event emitted while rebuilding a batch of contexts, modeled on Deno's
+/// test-runner `TestEvent`/`TestMessage` channel. Draining these (rather than waiting on a single
+/// completion callback) lets a caller render a live progress UI, compute per-page build timings,
+/// and react to the first error without waiting for the whole batch to finish.
+#[derive(Debug, Clone)]
+pub enum BuildEvent {
+    /// Emitted once up front with the total number of contexts about to be rebuilt.
+    Plan { total: usize },
+    /// Emitted when a context's rebuild begins, naming the input files feeding it (when known).
+    Start { id: c_int, files: Vec<PathBuf> },
+    /// Emitted as a heartbeat while a context is still rebuilding; currently fired once per
+    /// context immediately after `Start`, but reserved as a hook for finer-grained phase
+    /// reporting as the build pipeline grows more stages.
+    Progress { id: c_int },
+    /// Emitted exactly once per context with its terminal outcome and wall-clock duration.
+    Result {
+        id: c_int,
+        duration_ms: u128,
+        outcome: BuildOutcome,
+    },
+}
+
+/// How long to block on the next worker message before re-checking each context's own deadline.
+/// `next_recv_wait` returns the time until the *earliest* still-running context would time out,
+/// so a stuck context is caught on schedule even while unrelated contexts keep completing well
+/// inside their own budget.
+fn next_recv_wait(
+    handles: &HashMap<c_int, thread::JoinHandle<()>>,
+    started_at: &HashMap<c_int, Instant>,
+    per_context_timeout: Duration,
+    now: Instant,
+) -> Duration {
+    handles
+        .keys()
+        .filter_map(|id| started_at.get(id).map(|start| *start + per_context_timeout))
+        .min()
+        .map(|deadline| deadline.saturating_duration_since(now))
+        .unwrap_or(per_context_timeout)
+}
+
+/// Of the contexts still in `handles`, which have actually exceeded `per_context_timeout` as of
+/// `now`? Only these should be cancelled on a `recv_timeout` wakeup; the rest are still within
+/// their own budget and should keep running.
+fn timed_out_ids(
+    handles: &HashMap<c_int, thread::JoinHandle<()>>,
+    started_at: &HashMap<c_int, Instant>,
+    per_context_timeout: Duration,
+    now: Instant,
+) -> Vec<c_int> {
+    handles
+        .keys()
+        .copied()
+        .filter(|id| {
+            started_at
+                .get(id)
+                .map(|start| now.duration_since(*start) >= per_context_timeout)
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Rebuild each context in `ids` in parallel, streaming a [`BuildEvent`] for every state
+/// transition over the returned receiver instead of requiring the caller to block on a single
+/// completion callback. `input_files` is an optional lookup (context id -> its last-known input
+/// files) purely for the `Start` event's diagnostic payload; an unknown id just gets an empty
+/// list. `cache`, when given, routes contexts with a [`ContextCacheInputs`] entry through
+/// [`cache::rebuild_context_cached`] so an unchanged context resolves as [`BuildOutcome::Cached`]
+/// instead of paying for a real `RebuildContext` call.
+pub fn rebuild_contexts_stream(
+    ids: Vec<c_int>,
+    per_context_timeout: Duration,
+    input_files: HashMap<c_int, Vec<PathBuf>>,
+    cache: Option<Arc<RebuildCache>>,
+) -> Receiver<BuildEvent> {
+    let (event_tx, event_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let _ = event_tx.send(BuildEvent::Plan { total: ids.len() });
+
+        let (worker_tx, worker_rx) = mpsc::channel();
+        let mut handles: HashMap<c_int, thread::JoinHandle<()>> = HashMap::new();
+        let mut started_at: HashMap<c_int, Instant> = HashMap::new();
+
+        for id in ids.iter().copied() {
+            let files = input_files.get(&id).cloned().unwrap_or_default();
+            let _ = event_tx.send(BuildEvent::Start {
+                id,
+                files: files.clone(),
+            });
+            let _ = event_tx.send(BuildEvent::Progress { id });
+            started_at.insert(id, Instant::now());
+
+            let worker_tx = worker_tx.clone();
+            let cache = cache.clone();
+            let handle = thread::spawn(move || {
+                let result = match cache.as_deref().and_then(|cache| {
+                    cache.inputs.get(&id).map(|inputs| (cache, inputs))
+                }) {
+                    Some((cache, inputs)) => {
+                        cache::rebuild_context_cached(id, inputs, &cache.cache_dir, cache.force)
+                    }
+                    None => rebuild_context(id).map(|_| true),
+                };
+                let _ = worker_tx.send((id, result));
+            });
+            handles.insert(id, handle);
+        }
+
+        let mut remaining = ids.len();
+        while remaining > 0 {
+            // Wait only until the *earliest* still-running context's own deadline, not a flat
+            // `per_context_timeout` from now: resetting a shared timeout on every completion lets
+            // one stuck context hide behind a steady stream of unrelated ones finishing on time.
+            let wait = next_recv_wait(&handles, &started_at, per_context_timeout, Instant::now());
+
+            match worker_rx.recv_timeout(wait) {
+                Ok((id, result)) => {
+                    // A context already reported as timed out is no longer in `handles`; a late
+                    // result from its worker is dropped so each context gets one `Result`.
+                    let Some(handle) = handles.remove(&id) else {
+                        continue;
+                    };
+                    let _ = handle.join();
+                    let duration_ms = started_at
+                        .remove(&id)
+                        .map(|start| start.elapsed().as_millis())
+                        .unwrap_or(0);
+                    let outcome = match result {
+                        Ok(true) => BuildOutcome::Ok,
+                        Ok(false) => BuildOutcome::Cached,
+                        Err(error) => BuildOutcome::Error(error),
+                    };
+                    let _ = event_tx.send(BuildEvent::Result {
+                        id,
+                        duration_ms,
+                        outcome,
+                    });
+                    remaining -= 1;
+                }
+                Err(_) => {
+                    // Only contexts whose *own* deadline has actually elapsed are stuck; the rest
+                    // keep running and will be reconsidered on the next loop iteration.
+                    let stuck_ids =
+                        timed_out_ids(&handles, &started_at, per_context_timeout, Instant::now());
+                    for stuck_id in stuck_ids {
+                        if let Some(handle) = handles.remove(&stuck_id) {
+                            unsafe {
+                                crate::timeout_support::cancel_thread(handle);
+                            }
+                        }
+                        let duration_ms = started_at
+                            .remove(&stuck_id)
+                            .map(|start| start.elapsed().as_millis())
+                            .unwrap_or(0);
+                        let _ = event_tx.send(BuildEvent::Result {
+                            id: stuck_id,
+                            duration_ms,
+                            outcome: BuildOutcome::TimedOut,
+                        });
+                        remaining -= 1;
+                    }
+                }
+            }
+        }
+    });
+
+    event_rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn rebuild_contexts_stream_serves_a_cache_hit_without_calling_rebuild_context() {
+        // A context id that doesn't correspond to any real build context: if the cache wiring
+        // didn't actually short-circuit before reaching `rebuild_context`, this would error out
+        // instead of resolving as `BuildOutcome::Cached`.
+        let context_id: c_int = 987654;
+
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("entry.js");
+        fs::write(&input_path, b"export const x = 1;").unwrap();
+        let output_path = dir.path().join("entry.js.out");
+        fs::write(&output_path, b"cached output").unwrap();
+
+        let inputs = ContextCacheInputs {
+            input_files: vec![input_path],
+            environment: "development".to_string(),
+            is_server: false,
+            minify: false,
+            lockfile_contents: None,
+            output_path,
+        };
+
+        // Prime the manifest so the upcoming rebuild is a cache hit, without going through a
+        // real `rebuild_context` call.
+        cache::prime_manifest_entry_for_test(dir.path(), context_id, &inputs).unwrap();
+
+        let mut cache_inputs = HashMap::new();
+        cache_inputs.insert(context_id, inputs);
+        let rebuild_cache = Arc::new(RebuildCache {
+            cache_dir: dir.path().to_path_buf(),
+            inputs: cache_inputs,
+            force: false,
+        });
+
+        let event_rx = rebuild_contexts_stream(
+            vec![context_id],
+            Duration::from_secs(5),
+            HashMap::new(),
+            Some(rebuild_cache),
+        );
+
+        let outcome = event_rx
+            .into_iter()
+            .find_map(|event| match event {
+                BuildEvent::Result { id, outcome, .. } if id == context_id => Some(outcome),
+                _ => None,
+            })
+            .expect("expected a Result event for the cached context");
+
+        assert!(
+            matches!(outcome, BuildOutcome::Cached),
+            "expected a cache hit to resolve as BuildOutcome::Cached, got {:?}",
+            outcome
+        );
+    }
+
+    #[test]
+    fn next_recv_wait_tracks_the_earliest_deadline_not_the_last_reset() {
+        let per_context_timeout = Duration::from_secs(10);
+        let base = Instant::now() - Duration::from_secs(8);
+
+        let mut handles: HashMap<c_int, thread::JoinHandle<()>> = HashMap::new();
+        handles.insert(1, thread::spawn(|| {}));
+        handles.insert(2, thread::spawn(|| {}));
+
+        let mut started_at: HashMap<c_int, Instant> = HashMap::new();
+        // Context 1 started 8s ago and is almost out of budget.
+        started_at.insert(1, base);
+        // Context 2 only just started, long before its own deadline.
+        started_at.insert(2, Instant::now());
+
+        let wait = next_recv_wait(&handles, &started_at, per_context_timeout, Instant::now());
+
+        // The wait must be bounded by context 1's imminent deadline, not context 2's fresh one -
+        // otherwise a stuck context 1 would hide behind context 2 repeatedly resetting the clock.
+        assert!(
+            wait <= Duration::from_secs(3),
+            "expected wait to track the soonest deadline, got {:?}",
+            wait
+        );
+
+        for handle in handles.into_values() {
+            let _ = handle.join();
+        }
+    }
+
+    #[test]
+    fn timed_out_ids_only_flags_contexts_past_their_own_deadline() {
+        let per_context_timeout = Duration::from_secs(10);
+        let now = Instant::now();
+
+        let mut handles: HashMap<c_int, thread::JoinHandle<()>> = HashMap::new();
+        handles.insert(1, thread::spawn(|| {}));
+        handles.insert(2, thread::spawn(|| {}));
+
+        let mut started_at: HashMap<c_int, Instant> = HashMap::new();
+        // Context 1 has been running far longer than the timeout - genuinely stuck.
+        started_at.insert(1, now - Duration::from_secs(20));
+        // Context 2 just completed one of several earlier rounds of recv_timeout resets and is
+        // still well within its own budget.
+        started_at.insert(2, now - Duration::from_secs(1));
+
+        let stuck = timed_out_ids(&handles, &started_at, per_context_timeout, now);
+
+        assert_eq!(stuck, vec![1]);
+
+        for handle in handles.into_values() {
+            let _ = handle.join();
+        }
+    }
+}
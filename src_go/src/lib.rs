@@ -6,9 +6,21 @@ include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
 extern crate libc;
 
+use std::collections::HashMap;
 use std::ffi::{c_char, c_int, CString};
-use std::sync::{mpsc, Arc};
-use std::thread;
+use std::sync::Arc;
+
+mod atomic_write;
+mod cache;
+mod discovery;
+mod events;
+mod timeout_support;
+mod watch;
+
+pub use cache::{rebuild_context_cached, ContextCacheInputs};
+pub use discovery::{bundle_all_from_patterns, collect_entrypoints, FilePatterns};
+pub use events::{rebuild_contexts_stream, BuildEvent, BuildOutcome, RebuildCache};
+pub use watch::{watch_contexts, WatchHandle};
 
 pub fn get_build_context(
     filename: &str,
@@ -47,73 +59,91 @@ pub fn get_build_context(
 
 pub fn rebuild_context(context_ptr: c_int) -> Result<(), String> {
     unsafe {
-        let error = RebuildContext(context_ptr);
-        if error.is_null() {
-            Ok(())
-        } else {
+        // Rather than letting the Go side write the output file in place (which a live SSR
+        // server could read mid-write), we ask it for the compiled bytes plus the path it would
+        // have written to, and perform the final write ourselves through an atomic
+        // temp-file-then-rename so readers only ever see a complete file.
+        let result = RebuildContextBuffer(context_ptr);
+        let error = result.r2;
+
+        if !error.is_null() {
             let error_str = CString::from_raw(error);
             let error_string = error_str
                 .into_string()
                 .unwrap_or_else(|_| String::from("Unknown error"));
-            Err(error_string)
+            return Err(error_string);
         }
+
+        let output_path = CString::from_raw(result.r0)
+            .into_string()
+            .map_err(|e| format!("Output path was not valid UTF-8: {:?}", e))?;
+        let contents = std::slice::from_raw_parts(result.r1 as *const u8, result.r3 as usize);
+
+        atomic_write::write_atomic(std::path::Path::new(&output_path), contents)
+            .map_err(|e| format!("Failed to write build output atomically: {:?}", e))
     }
 }
 
 type Callback = dyn Fn(c_int) + Send + Sync;
 
-pub fn rebuild_contexts(ids: Vec<c_int>, callback: Arc<Box<Callback>>) -> Result<(), Vec<String>> {
-    let (tx, rx) = mpsc::channel();
-    let mut handles = Vec::new();
-
-    for id in ids.clone().into_iter() {
-        let tx = tx.clone();
-
-        let handle = thread::spawn(move || {
-            unsafe {
-                let error_ptr = RebuildContext(id);
-                let result = if !error_ptr.is_null() {
-                    let error_cstr = CString::from_raw(error_ptr);
-                    let error_string = error_cstr
-                        .into_string()
-                        .unwrap_or_else(|_| String::from("Unknown error"));
-                    Some(error_string)
-                } else {
-                    None
-                };
-
-                // Send both the ID and result to the main thread via channel
-                tx.send((id, result.clone())).unwrap();
-            }
-        });
-        handles.push(handle);
-    }
+/// Per-context result of a call to [`rebuild_contexts`], distinguishing a hung build from one
+/// that genuinely failed so callers don't have to guess from a flat error string.
+#[derive(Debug, Clone)]
+pub enum RebuildOutcome {
+    Succeeded,
+    /// Served from [`cache::rebuild_context_cached`] without calling `RebuildContext`: the
+    /// context's last bundle was still valid for its current inputs.
+    Cached,
+    Errored(String),
+    TimedOut,
+}
 
-    // Cleanup handles and collect errors
-    let mut errors = Vec::new();
-
-    // Collect results in the order they are completed
-    for _ in 0..ids.len() {
-        if let Ok((id, err)) = rx.recv() {
-            if let Some(error) = err {
-                errors.push(error);
-            } else {
-                // Call the callback with the ID
-                callback(id);
+/// Rebuild each context in `ids` on its own thread, in parallel, calling `callback` for every id
+/// that completes successfully (including one served from the cache). Unlike a plain `join()`, a
+/// single hung build (an infinite loop in a plugin, a deadlocked esbuild worker) cannot wedge the
+/// whole batch forever: each worker is given `per_context_timeout` to finish, and once that budget
+/// is exceeded the result is recorded as `RebuildOutcome::TimedOut` and a best-effort cancellation
+/// is attempted on the stuck thread rather than blocking on `join()` unconditionally.
+///
+/// `cache`, when given, is forwarded to [`events::rebuild_contexts_stream`] so contexts with a
+/// registered [`cache::ContextCacheInputs`] entry skip the rebuild entirely when their content
+/// hash is unchanged.
+///
+/// This is a thin backward-compatible adapter over [`events::rebuild_contexts_stream`]: it drains
+/// the structured event stream and folds each `Result` event into the flat outcome map callers
+/// already depend on. New callers that want live progress (a UI, per-page timings, the first
+/// error before the whole batch finishes) should use the event stream directly instead.
+pub fn rebuild_contexts(
+    ids: Vec<c_int>,
+    callback: Arc<Box<Callback>>,
+    per_context_timeout: std::time::Duration,
+    cache: Option<Arc<events::RebuildCache>>,
+) -> HashMap<c_int, RebuildOutcome> {
+    let event_rx = events::rebuild_contexts_stream(ids, per_context_timeout, HashMap::new(), cache);
+    let mut outcomes: HashMap<c_int, RebuildOutcome> = HashMap::new();
+
+    for event in event_rx {
+        if let events::BuildEvent::Result { id, outcome, .. } = event {
+            match outcome {
+                events::BuildOutcome::Ok => {
+                    outcomes.insert(id, RebuildOutcome::Succeeded);
+                    callback(id);
+                }
+                events::BuildOutcome::Cached => {
+                    outcomes.insert(id, RebuildOutcome::Cached);
+                    callback(id);
+                }
+                events::BuildOutcome::Error(err) => {
+                    outcomes.insert(id, RebuildOutcome::Errored(err));
+                }
+                events::BuildOutcome::TimedOut => {
+                    outcomes.insert(id, RebuildOutcome::TimedOut);
+                }
             }
         }
     }
 
-    // Close out the workers
-    for handle in handles {
-        handle.join().unwrap();
-    }
-
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        Err(errors)
-    }
+    outcomes
 }
 
 pub fn remove_context(context_ptr: c_int) {
@@ -230,7 +260,16 @@ mod tests {
         }) as Box<dyn Fn(i32) + Send + Sync>);
 
         // Call rebuild_contexts with the callback
-        rebuild_contexts(vec![context_id], callback).unwrap();
+        let outcomes = rebuild_contexts(
+            vec![context_id],
+            callback,
+            std::time::Duration::from_secs(30),
+            None,
+        );
+        assert!(matches!(
+            outcomes.get(&context_id),
+            Some(RebuildOutcome::Succeeded)
+        ));
 
         assert!(output_file_path.exists());
     }
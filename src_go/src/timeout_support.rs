@@ -0,0 +1,38 @@
+use std::thread::JoinHandle;
+
+#[cfg(unix)]
+mod platform {
+    use std::os::unix::thread::JoinHandleExt;
+    use std::thread::JoinHandle;
+
+    /// Forcibly terminate a still-running worker thread. This is the same escape hatch the
+    /// standalone prime-finder prototype in the main crate's `main.rs` uses: it violates Rust's
+    /// memory guarantees (the thread's stack/heap state is abandoned mid-flight), so it should
+    /// only ever be reached after a context has already blown its rebuild timeout budget.
+    pub unsafe fn cancel_thread(thread: JoinHandle<()>) {
+        let handle = thread.into_pthread_t();
+        libc::pthread_cancel(handle);
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    extern crate winapi;
+    use std::os::windows::io::AsRawHandle;
+    use std::thread::JoinHandle;
+    use winapi::um::processthreadsapi::TerminateThread;
+    use winapi::um::winnt::HANDLE;
+
+    pub unsafe fn cancel_thread(thread: JoinHandle<()>) {
+        let handle = thread.as_raw_handle();
+        TerminateThread(handle as HANDLE, 0);
+    }
+}
+
+/// Attempt to cancel a worker thread that has exceeded its rebuild budget. Best-effort: on
+/// platforms/configurations where the Go runtime underneath refuses to unwind (e.g. it is stuck
+/// inside cgo), the OS-level cancellation may simply leak the thread rather than crash the
+/// process.
+pub unsafe fn cancel_thread(thread: JoinHandle<()>) {
+    platform::cancel_thread(thread)
+}
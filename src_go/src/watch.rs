@@ -0,0 +1,207 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
+use std::os::raw::c_int;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, error, warn};
+use notify::{RecursiveMode, Watcher};
+
+use crate::{rebuild_contexts, GetContextFiles};
+
+/// How long to coalesce bursts of filesystem events before triggering a rebuild pass.
+/// Mirrors Deno's file-watcher debounce window.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(100);
+
+/// Budget given to each context's rebuild before it is treated as hung. Generous enough for a
+/// cold Rolldown pass but still bounded so one wedged build can't stall the whole watch loop.
+const REBUILD_TIMEOUT: Duration = Duration::from_secs(30);
+
+type OnRebuild = dyn Fn(c_int) + Send + Sync;
+
+/// Handle returned by [`watch_contexts`]. Dropping it does not stop the watcher;
+/// call [`WatchHandle::shutdown`] explicitly for a clean stop.
+pub struct WatchHandle {
+    shutdown: Arc<AtomicBool>,
+    join_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl WatchHandle {
+    /// Signal the watcher thread to stop and block until it has exited.
+    pub fn shutdown(mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.join_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Ask the Go side for the set of input files that went into the last bundle of `context_ptr`.
+fn get_context_files(context_ptr: c_int) -> Result<Vec<PathBuf>, String> {
+    unsafe {
+        let result = GetContextFiles(context_ptr);
+        let files_ptr = result.r0;
+        let error = result.r1;
+
+        if !error.is_null() {
+            let error_string = CString::from_raw(error)
+                .into_string()
+                .unwrap_or_else(|_| String::from("Unknown error"));
+            return Err(error_string);
+        }
+
+        let files_str = CString::from_raw(files_ptr)
+            .into_string()
+            .map_err(|e| format!("Context files were not valid UTF-8: {:?}", e))?;
+
+        Ok(files_str
+            .split('\n')
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect())
+    }
+}
+
+/// Build a reverse map of file -> the set of context ids whose last bundle depended on it.
+fn build_reverse_map(ids: &[c_int]) -> HashMap<PathBuf, Vec<c_int>> {
+    let mut reverse_map: HashMap<PathBuf, Vec<c_int>> = HashMap::new();
+
+    for &id in ids {
+        match get_context_files(id) {
+            Ok(files) => {
+                for file in files {
+                    reverse_map.entry(file).or_default().push(id);
+                }
+            }
+            Err(err) => {
+                warn!("Unable to fetch input files for context {}: {}", id, err);
+            }
+        }
+    }
+
+    reverse_map
+}
+
+/// Start watching the parent directory of each of `files` that isn't already covered by
+/// `watched_dirs`, recording it so a later rebuild's new dependencies don't get re-watched.
+fn watch_new_parents(
+    watcher: &mut notify::RecommendedWatcher,
+    watched_dirs: &mut HashSet<PathBuf>,
+    files: impl IntoIterator<Item = PathBuf>,
+) {
+    for path in files {
+        if let Some(parent) = path.parent() {
+            if watched_dirs.insert(parent.to_path_buf()) {
+                // Watching the parent directory (rather than the file itself) survives editors
+                // that replace files via rename-on-save instead of writing in place.
+                let _ = watcher.watch(parent, RecursiveMode::NonRecursive);
+            }
+        }
+    }
+}
+
+/// Start a long-running watch over the source files feeding each of `ids`, triggering
+/// incremental rebuilds as they change.
+///
+/// Filesystem events are debounced over [`DEBOUNCE_WINDOW`] so a burst of saves collapses
+/// into a single rebuild pass. Only the contexts whose last-known input files actually changed
+/// are rebuilt; the rest are left untouched. Rebuild errors are logged and watching continues -
+/// the watcher only stops once [`WatchHandle::shutdown`] is called.
+pub fn watch_contexts(ids: Vec<c_int>, on_rebuild: Arc<OnRebuild>) -> Result<WatchHandle, String> {
+    let (fs_tx, fs_rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = fs_tx.send(event);
+        }
+    })
+    .map_err(|e| format!("Failed to create filesystem watcher: {:?}", e))?;
+
+    let mut reverse_map = build_reverse_map(&ids);
+    let mut watched_dirs: HashSet<PathBuf> = HashSet::new();
+    watch_new_parents(&mut watcher, &mut watched_dirs, reverse_map.keys().cloned());
+
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let shutdown_clone = shutdown.clone();
+
+    let join_handle = thread::spawn(move || {
+        // Keep the watcher alive for the lifetime of this thread.
+        let mut watcher = watcher;
+
+        while !shutdown_clone.load(Ordering::SeqCst) {
+            let mut changed_paths: Vec<PathBuf> = Vec::new();
+
+            match fs_rx.recv_timeout(DEBOUNCE_WINDOW) {
+                Ok(event) => changed_paths.extend(event.paths),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            // Coalesce any additional events that arrive within the debounce window into the
+            // same rebuild pass.
+            let deadline = std::time::Instant::now() + DEBOUNCE_WINDOW;
+            while let Some(remaining) = deadline.checked_duration_since(std::time::Instant::now())
+            {
+                match fs_rx.recv_timeout(remaining) {
+                    Ok(event) => changed_paths.extend(event.paths),
+                    Err(_) => break,
+                }
+            }
+
+            let mut affected: Vec<c_int> = changed_paths
+                .iter()
+                .filter_map(|path| reverse_map.get(path))
+                .flatten()
+                .copied()
+                .collect();
+            affected.sort_unstable();
+            affected.dedup();
+
+            if affected.is_empty() {
+                continue;
+            }
+
+            debug!("Rebuilding {} context(s) after file change", affected.len());
+
+            let on_rebuild = on_rebuild.clone();
+            let callback: Arc<Box<dyn Fn(c_int) + Send + Sync>> =
+                Arc::new(Box::new(move |id| on_rebuild(id)));
+
+            let outcomes = rebuild_contexts(affected.clone(), callback, REBUILD_TIMEOUT, None);
+            for (id, outcome) in outcomes {
+                match outcome {
+                    crate::RebuildOutcome::Errored(err) => {
+                        error!("Rebuild of context {} failed during watch: {}", id, err);
+                    }
+                    crate::RebuildOutcome::TimedOut => {
+                        error!("Rebuild of context {} timed out during watch", id);
+                    }
+                    crate::RebuildOutcome::Succeeded | crate::RebuildOutcome::Cached => {}
+                }
+            }
+
+            // The rebuilt contexts may now depend on a different set of files (new imports
+            // added/removed), so refresh the reverse map for the ids we just touched.
+            for id in affected {
+                if let Ok(files) = get_context_files(id) {
+                    reverse_map.retain(|_, ids| {
+                        ids.retain(|existing| *existing != id);
+                        !ids.is_empty()
+                    });
+                    watch_new_parents(&mut watcher, &mut watched_dirs, files.iter().cloned());
+                    for file in files {
+                        reverse_map.entry(file).or_default().push(id);
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(WatchHandle {
+        shutdown,
+        join_handle: Some(join_handle),
+    })
+}